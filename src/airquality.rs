@@ -0,0 +1,199 @@
+// Air quality forecast from MET Norway, available for Norwegian locations
+// https://api.met.no/weatherapi/airqualityforecast/0.1/documentation
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::forecast::{fetch_json, read_stale_json, Context};
+use crate::location::Coordinates;
+
+/// MET's air quality index level, as used on luftkvalitet.info
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AqiLevel {
+    Low,
+    Moderate,
+    High,
+    VeryHigh,
+}
+
+impl AqiLevel {
+    /// MET's AQI runs from 1, with a new level starting at every whole number from 2
+    pub fn from_index(aqi: f64) -> Self {
+        if aqi < 2.0 {
+            Self::Low
+        } else if aqi < 3.0 {
+            Self::Moderate
+        } else if aqi < 4.0 {
+            Self::High
+        } else {
+            Self::VeryHigh
+        }
+    }
+
+    /// CSS class for the indicator
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::Low => "aqi-low",
+            Self::Moderate => "aqi-moderate",
+            Self::High => "aqi-high",
+            Self::VeryHigh => "aqi-very-high",
+        }
+    }
+}
+
+/// Air quality index and pollutant concentrations in µg/m³ for the current hour
+#[derive(Debug, PartialEq)]
+pub struct AirQuality {
+    pub aqi: f64,
+    pub pm25: Option<f64>,
+    pub pm10: Option<f64>,
+    pub no2: Option<f64>,
+    pub o3: Option<f64>,
+}
+
+impl AirQuality {
+    pub fn level(&self) -> AqiLevel {
+        AqiLevel::from_index(self.aqi)
+    }
+}
+
+const BACKOFF_FILE: &str = "backoff-airquality.json";
+/// Cached in place of the forecast where there is none, which parses as no air quality
+const UNAVAILABLE: &str = "{}";
+
+/// Fetch the air quality for the current hour, cached for an hour.
+/// Returns `None` outside Norway or if the forecast is unavailable.
+pub fn fetch_air_quality(ctx: &Context, coords: &Coordinates) -> Option<AirQuality> {
+    const CACHE_DURATION: Duration = Duration::from_secs(3600); // 1 hour
+    let cache_file = format!("airquality-{}-{}.json", coords.lat, coords.lon);
+
+    let url = format!(
+        "https://api.met.no/weatherapi/airqualityforecast/0.1/?lat={}&lon={}",
+        coords.lat, coords.lon
+    );
+
+    let data = match fetch_json(ctx, &url, &cache_file, CACHE_DURATION, BACKOFF_FILE) {
+        Ok(data) => data,
+        // Locations outside Norway are answered with 422. Remember that as long
+        // as a forecast would be, instead of asking again on every run.
+        Err(Error::HttpStatus(400..=499)) => {
+            let _ = ctx.cache.write(&cache_file, UNAVAILABLE, ctx.clock);
+            return None;
+        }
+        // Server and network errors may pass, so ask again next time
+        Err(_) => read_stale_json(ctx, &cache_file)?.0,
+    };
+
+    parse_air_quality(&data, ctx.clock.now())
+}
+
+fn parse_air_quality(data: &Value, now: DateTime<Utc>) -> Option<AirQuality> {
+    let parse_time = |value: &Value| value.as_str()?.parse::<DateTime<Utc>>().ok();
+    let entry = data["data"]["time"].as_array()?.iter().find(|entry| {
+        matches!((parse_time(&entry["from"]), parse_time(&entry["to"])), (Some(from), Some(to)) if from <= now && now < to)
+    })?;
+
+    let variables = &entry["variables"];
+    let value = |name: &str| variables[name]["value"].as_f64();
+    Some(AirQuality {
+        aqi: value("AQI")?,
+        pm25: value("pm25_concentration"),
+        pm10: value("pm10_concentration"),
+        no2: value("no2_concentration"),
+        o3: value("o3_concentration"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Cache;
+    use crate::clock::FixedClock;
+    use crate::http::mock::FixtureTransport;
+
+    const AIR_QUALITY_FIXTURE: &str = include_str!("../tests/fixtures/met_airqualityforecast.json");
+    const AIR_QUALITY_URL: &str = "https://api.met.no/weatherapi/airqualityforecast/0.1/";
+    const OSLO: Coordinates = Coordinates { lat: 59.9139, lon: 10.7522, altitude: None };
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn reads_current_hour() {
+        let data = serde_json::from_str(AIR_QUALITY_FIXTURE).unwrap();
+        let air_quality = parse_air_quality(&data, at("2024-06-01T14:20:00Z")).unwrap();
+        assert_eq!(
+            air_quality,
+            AirQuality { aqi: 1.9, pm25: Some(9.7), pm10: Some(17.5), no2: Some(32.8), o3: Some(78.0) }
+        );
+        assert_eq!(air_quality.level(), AqiLevel::Low);
+        assert_eq!(parse_air_quality(&data, at("2024-06-01T15:00:00Z")).unwrap().level(), AqiLevel::Moderate);
+        assert!(parse_air_quality(&data, at("2024-06-03T12:00:00Z")).is_none());
+    }
+
+    #[test]
+    fn fetches_and_caches_air_quality() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = FixtureTransport::default().route(AIR_QUALITY_URL, 200, AIR_QUALITY_FIXTURE);
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        assert_eq!(fetch_air_quality(&ctx, &OSLO).unwrap().aqi, 1.4);
+        clock.advance(chrono::Duration::minutes(50));
+        assert_eq!(fetch_air_quality(&ctx, &OSLO).unwrap().aqi, 1.6);
+        assert_eq!(http.requests.borrow().len(), 1);
+    }
+
+    #[test]
+    fn ignores_locations_outside_norway() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = FixtureTransport::default().route(AIR_QUALITY_URL, 422, "{}");
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        assert!(fetch_air_quality(&ctx, &Coordinates::new(48.8566, 2.3522)).is_none());
+        assert!(fetch_air_quality(&ctx, &Coordinates::new(48.8566, 2.3522)).is_none());
+        assert_eq!(http.requests.borrow().len(), 1);
+    }
+
+    #[test]
+    fn retries_after_server_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = FixtureTransport::default().route(AIR_QUALITY_URL, 200, AIR_QUALITY_FIXTURE);
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+        assert!(fetch_air_quality(&ctx, &OSLO).is_some());
+
+        // The forecast from before still covers the current hour
+        clock.advance(chrono::Duration::hours(2));
+        let failing = FixtureTransport::default().route(AIR_QUALITY_URL, 503, "");
+        let ctx = Context { clock: &clock, http: &failing, cache: &cache };
+        assert_eq!(fetch_air_quality(&ctx, &OSLO).unwrap().aqi, 1.9);
+        assert!(fetch_air_quality(&ctx, &OSLO).is_some());
+        assert_eq!(failing.requests.borrow().len(), 2);
+    }
+
+    #[test]
+    fn backs_off_when_throttled() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let throttled = FixtureTransport::default().route_with_headers(AIR_QUALITY_URL, 429, &[("retry-after", "120")], "");
+        let ctx = Context { clock: &clock, http: &throttled, cache: &cache };
+
+        assert!(fetch_air_quality(&ctx, &OSLO).is_none());
+        assert!(fetch_air_quality(&ctx, &OSLO).is_none());
+        assert_eq!(throttled.requests.borrow().len(), 1);
+
+        clock.advance(chrono::Duration::minutes(3));
+        let http = FixtureTransport::default().route(AIR_QUALITY_URL, 200, AIR_QUALITY_FIXTURE);
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+        assert!(fetch_air_quality(&ctx, &OSLO).is_some());
+    }
+}
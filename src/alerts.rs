@@ -0,0 +1,345 @@
+// Weather warnings from MetAlerts as CAP documents, available for Norway
+// https://api.met.no/weatherapi/metalerts/2.0/documentation
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use roxmltree::{Document, Node};
+
+use crate::error::{Error, Result};
+use crate::forecast::{backoff_until, record_throttling, Context};
+use crate::lang::Lang;
+use crate::location::Coordinates;
+
+const METALERTS_URL: &str = "https://api.met.no/weatherapi/metalerts/2.0/current";
+const BACKOFF_FILE: &str = "backoff-alerts.json";
+
+/// MET's awareness level of a warning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Yellow,
+    Orange,
+    Red,
+}
+
+impl Severity {
+    /// Parse an `awareness_level` value like `2; yellow; Moderate`.
+    fn from_awareness_level(value: &str) -> Option<Self> {
+        match value.split(';').nth(1)?.trim() {
+            "yellow" => Some(Self::Yellow),
+            "orange" => Some(Self::Orange),
+            "red" => Some(Self::Red),
+            _ => None,
+        }
+    }
+
+    /// CSS class for the indicator
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::Yellow => "alert-yellow",
+            Self::Orange => "alert-orange",
+            Self::Red => "alert-red",
+        }
+    }
+}
+
+/// A weather warning in the language closest to the user's
+#[derive(Debug)]
+pub struct Alert {
+    pub identifier: String,
+    pub severity: Severity,
+    pub headline: String,
+    pub description: String,
+    pub instruction: Option<String>,
+    pub onset: Option<DateTime<Utc>>,
+    pub expires: DateTime<Utc>,
+    /// Warning areas as polygons of (lat, lon) points
+    pub areas: Vec<Vec<(f64, f64)>>,
+}
+
+impl Alert {
+    /// Whether the location lies inside one of the warning areas.
+    pub fn covers(&self, coords: &Coordinates) -> bool {
+        self.areas.iter().any(|polygon| point_in_polygon(coords.lat, coords.lon, polygon))
+    }
+}
+
+/// Fetch warnings in effect now or later for the location, most severe first.
+/// The feed is cached for 10 minutes and each warning until it is evicted, since
+/// updates to a warning are published under a new identifier.
+pub fn fetch_alerts(ctx: &Context, coords: &Coordinates, lang: &Lang) -> Result<Vec<Alert>> {
+    const FEED_CACHE_DURATION: Duration = Duration::from_secs(600); // 10 minutes
+    const CAP_CACHE_DURATION: Duration = Duration::from_secs(7 * 24 * 3600);
+
+    let feed_file = format!("metalerts-{}-{}.rss", coords.lat, coords.lon);
+    let feed_url = format!("{}.rss?lat={}&lon={}", METALERTS_URL, coords.lat, coords.lon);
+    let feed = fetch_cached(ctx, &feed_url, &feed_file, FEED_CACHE_DURATION)?;
+
+    let now = ctx.clock.now();
+    let mut alerts = Vec::new();
+    for (guid, link) in parse_feed(&feed)? {
+        // Identifiers are OIDs; anything else should not end up in a file name
+        if !guid.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-') {
+            continue;
+        }
+        // One broken warning should not hide the others
+        let alert = fetch_cached(ctx, &link, &format!("metalerts-{}.xml", guid), CAP_CACHE_DURATION)
+            .and_then(|cap| parse_cap(&cap, lang));
+        match alert {
+            Ok(Some(alert)) if alert.expires > now && alert.covers(coords) => alerts.push(alert),
+            Ok(_) => {}
+            Err(e) => eprintln!("Warning: Could not read weather alert {}: {}", guid, e),
+        }
+    }
+
+    alerts.sort_by_key(|alert| std::cmp::Reverse(alert.severity));
+    Ok(alerts)
+}
+
+fn fetch_cached(ctx: &Context, url: &str, cache_file: &str, max_age: Duration) -> Result<String> {
+    if let Some(contents) = ctx.cache.read(cache_file, max_age, ctx.clock) {
+        return Ok(contents);
+    }
+
+    if let Some(until) = backoff_until(ctx, BACKOFF_FILE).filter(|until| *until > ctx.clock.now()) {
+        return Err(Error::Throttled(until));
+    }
+
+    let response = ctx.http.get(url)?;
+    match response.status {
+        200..=299 => {}
+        429 => {
+            let retry_after = response.retry_after(ctx.clock.now());
+            return Err(Error::Throttled(record_throttling(ctx, BACKOFF_FILE, retry_after)));
+        }
+        status => return Err(Error::HttpStatus(status)),
+    }
+    let _ = ctx.cache.remove(BACKOFF_FILE);
+    if let Err(e) = ctx.cache.write(cache_file, &response.body, ctx.clock) {
+        eprintln!("Warning: Could not write cache file {}: {}", cache_file, e);
+    }
+    Ok(response.body)
+}
+
+/// Identifier and CAP document link of each item in the RSS feed.
+fn parse_feed(xml: &str) -> Result<Vec<(String, String)>> {
+    let doc = Document::parse(xml).map_err(|e| Error::Parse(e.to_string()))?;
+    Ok(doc
+        .descendants()
+        .filter(|node| node.has_tag_name("item"))
+        .filter_map(|item| Some((child_text(item, "guid")?.to_string(), child_text(item, "link")?.to_string())))
+        .collect())
+}
+
+/// Parse a CAP alert, preferring the `info` block in the user's language.
+/// Returns `None` for documents that are not an actual MET warning, such as cancellations.
+fn parse_cap(xml: &str, lang: &Lang) -> Result<Option<Alert>> {
+    let doc = Document::parse(xml).map_err(|e| Error::Parse(e.to_string()))?;
+    let root = doc.root_element();
+    if child_text(root, "status") != Some("Actual") || child_text(root, "msgType") == Some("Cancel") {
+        return Ok(None);
+    }
+
+    let infos: Vec<Node> = root.children().filter(|node| node.has_tag_name("info")).collect();
+    let Some(info) = infos
+        .iter()
+        .find(|info| child_text(**info, "language").is_some_and(|language| language.starts_with(lang.alert_language())))
+        .or_else(|| infos.first())
+    else {
+        return Ok(None);
+    };
+
+    let severity = info
+        .children()
+        .filter(|node| node.has_tag_name("parameter"))
+        .find(|parameter| child_text(*parameter, "valueName") == Some("awareness_level"))
+        .and_then(|parameter| child_text(parameter, "value"))
+        .and_then(Severity::from_awareness_level);
+    let expires = child_text(*info, "expires").and_then(parse_time);
+    let (Some(severity), Some(expires)) = (severity, expires) else {
+        return Err(Error::Parse("CAP alert without awareness level or expiry".to_string()));
+    };
+
+    let areas = info
+        .children()
+        .filter(|node| node.has_tag_name("area"))
+        .flat_map(|area| area.children().filter(|node| node.has_tag_name("polygon")))
+        .filter_map(|polygon| parse_polygon(polygon.text()?))
+        .collect();
+
+    Ok(Some(Alert {
+        identifier: child_text(root, "identifier").unwrap_or_default().to_string(),
+        severity,
+        headline: child_text(*info, "headline").unwrap_or_default().to_string(),
+        description: child_text(*info, "description").unwrap_or_default().to_string(),
+        instruction: child_text(*info, "instruction").map(str::to_string),
+        onset: child_text(*info, "onset").and_then(parse_time),
+        expires,
+        areas,
+    }))
+}
+
+fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.children()
+        .find(|child| child.has_tag_name(name))
+        .and_then(|child| child.text())
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time).ok().map(|time| time.with_timezone(&Utc))
+}
+
+/// Parse a CAP polygon: space separated `lat,lon` pairs.
+fn parse_polygon(text: &str) -> Option<Vec<(f64, f64)>> {
+    text.split_whitespace()
+        .map(|pair| {
+            let (lat, lon) = pair.split_once(',')?;
+            Some((lat.parse().ok()?, lon.parse().ok()?))
+        })
+        .collect::<Option<Vec<_>>>()
+        .filter(|points| points.len() >= 3)
+}
+
+/// Ray casting test; warning areas are small enough to treat coordinates as planar.
+fn point_in_polygon(lat: f64, lon: f64, polygon: &[(f64, f64)]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let (lat_i, lon_i) = polygon[i];
+        let (lat_j, lon_j) = polygon[j];
+        if (lat_i > lat) != (lat_j > lat) && lon < (lon_j - lon_i) * (lat - lat_i) / (lat_j - lat_i) + lon_i {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Cache;
+    use crate::clock::FixedClock;
+    use crate::http::mock::FixtureTransport;
+
+    const FEED_FIXTURE: &str = include_str!("../tests/fixtures/met_metalerts_current.rss");
+    const RAIN_OSLO_FIXTURE: &str = include_str!("../tests/fixtures/met_metalerts_cap_rain_oslo.xml");
+    const WIND_BERGEN_FIXTURE: &str = include_str!("../tests/fixtures/met_metalerts_cap_wind_bergen.xml");
+    const OSLO: Coordinates = Coordinates { lat: 59.9139, lon: 10.7522, altitude: None };
+    const BERGEN: Coordinates = Coordinates { lat: 60.3913, lon: 5.3221, altitude: None };
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    fn metalerts_transport() -> FixtureTransport {
+        FixtureTransport::default()
+            .route("https://api.met.no/weatherapi/metalerts/2.0/current.rss", 200, FEED_FIXTURE)
+            .route("https://api.met.no/weatherapi/metalerts/2.0/current?cap=2.49.0.1.578.0.20240601090000.001", 200, RAIN_OSLO_FIXTURE)
+            .route("https://api.met.no/weatherapi/metalerts/2.0/current?cap=2.49.0.1.578.0.20240601083000.002", 200, WIND_BERGEN_FIXTURE)
+    }
+
+    #[test]
+    fn parses_cap_alert_in_preferred_language() {
+        let alert = parse_cap(RAIN_OSLO_FIXTURE, &Lang::NB).unwrap().unwrap();
+        assert_eq!(alert.identifier, "2.49.0.1.578.0.20240601090000.001");
+        assert_eq!(alert.severity, Severity::Yellow);
+        assert_eq!(alert.headline, "Styrtregn, gult nivå");
+        assert_eq!(alert.instruction.as_deref(), Some("Sørg for at sluk og stikkrenner ikke er tette."));
+        assert_eq!(alert.onset, Some(at("2024-06-01T14:00:00Z")));
+        assert_eq!(alert.expires, at("2024-06-01T22:00:00Z"));
+
+        let alert = parse_cap(RAIN_OSLO_FIXTURE, &Lang::DE).unwrap().unwrap();
+        assert_eq!(alert.headline, "Heavy rain, yellow level");
+
+        // Only an English version is published
+        let alert = parse_cap(WIND_BERGEN_FIXTURE, &Lang::NB).unwrap().unwrap();
+        assert_eq!(alert.severity, Severity::Orange);
+        assert_eq!(alert.headline, "Wind, orange level");
+    }
+
+    #[test]
+    fn checks_location_against_alert_areas() {
+        let alert = parse_cap(RAIN_OSLO_FIXTURE, &Lang::EN).unwrap().unwrap();
+        assert!(alert.covers(&OSLO));
+        assert!(!alert.covers(&BERGEN));
+    }
+
+    #[test]
+    fn ignores_cancelled_alerts() {
+        let cancelled = RAIN_OSLO_FIXTURE.replace("<msgType>Alert</msgType>", "<msgType>Cancel</msgType>");
+        assert!(parse_cap(&cancelled, &Lang::EN).unwrap().is_none());
+    }
+
+    #[test]
+    fn fetches_alerts_covering_location_and_caches_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:00:00Z"));
+        let http = metalerts_transport();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        let alerts = fetch_alerts(&ctx, &OSLO, &Lang::EN).unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].headline, "Heavy rain, yellow level");
+        assert_eq!(http.requests.borrow().len(), 3);
+
+        let alerts = fetch_alerts(&ctx, &BERGEN, &Lang::EN).unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].severity, Severity::Orange);
+        // The feed is per location, the warnings themselves come from the cache
+        assert_eq!(http.requests.borrow().len(), 4);
+    }
+
+    #[test]
+    fn skips_alerts_that_cannot_be_fetched() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:00:00Z"));
+        let http = FixtureTransport::default()
+            .route("https://api.met.no/weatherapi/metalerts/2.0/current.rss", 200, FEED_FIXTURE)
+            .route("https://api.met.no/weatherapi/metalerts/2.0/current?cap=2.49.0.1.578.0.20240601090000.001", 200, RAIN_OSLO_FIXTURE)
+            .route("https://api.met.no/weatherapi/metalerts/2.0/current?cap=2.49.0.1.578.0.20240601083000.002", 500, "");
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        let alerts = fetch_alerts(&ctx, &OSLO, &Lang::EN).unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].headline, "Heavy rain, yellow level");
+    }
+
+    #[test]
+    fn backs_off_when_throttled() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:00:00Z"));
+        let throttled = FixtureTransport::default().route_with_headers(
+            "https://api.met.no/weatherapi/metalerts/2.0/current.rss",
+            429,
+            &[("retry-after", "120")],
+            "",
+        );
+        let ctx = Context { clock: &clock, http: &throttled, cache: &cache };
+
+        let error = fetch_alerts(&ctx, &OSLO, &Lang::EN).unwrap_err();
+        assert!(matches!(error, Error::Throttled(until) if until == at("2024-06-01T12:02:00Z")));
+        assert!(matches!(fetch_alerts(&ctx, &OSLO, &Lang::EN), Err(Error::Throttled(_))));
+        assert_eq!(throttled.requests.borrow().len(), 1);
+
+        clock.advance(chrono::Duration::minutes(3));
+        let http = metalerts_transport();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+        assert_eq!(fetch_alerts(&ctx, &OSLO, &Lang::EN).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn drops_expired_alerts() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T23:00:00Z"));
+        let http = metalerts_transport();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        assert!(fetch_alerts(&ctx, &OSLO, &Lang::EN).unwrap().is_empty());
+    }
+}
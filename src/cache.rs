@@ -0,0 +1,192 @@
+// Per-user cache in $XDG_CACHE_HOME/waybar-weather-info
+// https://specifications.freedesktop.org/basedir-spec/latest/
+use std::env;
+use std::fs::{self, DirBuilder, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+
+use crate::clock::Clock;
+
+const CACHE_DIR_NAME: &str = "waybar-weather-info";
+/// Files holding state rather than cached data, which must outlive any max age:
+/// sent notifications, throttling backoff and API deprecation markers
+const STATE_FILE_PREFIXES: [&str; 3] = ["notifications.json", "backoff-", "api-deprecated-"];
+
+pub struct CacheEntry {
+    pub name: String,
+    pub size: u64,
+    pub age: Duration,
+}
+
+/// Cache files whose age is measured against a [`Clock`].
+/// Files get the clock's time as modification time when written.
+pub struct Cache {
+    dir: Option<PathBuf>,
+}
+
+impl Cache {
+    /// Cache in the user's cache directory, disabled if neither `XDG_CACHE_HOME` nor `HOME` is usable.
+    pub fn from_env() -> Self {
+        let base = match env::var_os("XDG_CACHE_HOME").map(PathBuf::from) {
+            // The spec says relative paths must be ignored
+            Some(dir) if dir.is_absolute() => Some(dir),
+            _ => env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")),
+        };
+        Self { dir: base.map(|base| base.join(CACHE_DIR_NAME)) }
+    }
+
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir: Some(dir) }
+    }
+
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// Read a cache file if it is younger than `max_age`.
+    pub fn read(&self, name: &str, max_age: Duration, clock: &dyn Clock) -> Option<String> {
+        let (contents, age) = self.read_with_age(name, clock)?;
+        (age < max_age).then_some(contents)
+    }
+
+    /// Read a cache file regardless of age, together with how old it is.
+    pub fn read_with_age(&self, name: &str, clock: &dyn Clock) -> Option<(String, Duration)> {
+        let path = self.dir.as_ref()?.join(name);
+        let age = age(&path, clock)?;
+        let contents = fs::read_to_string(path).ok()?;
+        Some((contents, age))
+    }
+
+    /// Write a cache file atomically with owner-only permissions.
+    pub fn write(&self, name: &str, contents: &str, clock: &dyn Clock) -> io::Result<()> {
+        let dir = self.dir.as_ref().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no cache directory"))?;
+        DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+
+        // Write to a temporary file next to the target and rename it into place,
+        // so readers never see a half-written file
+        let tmp_path = dir.join(format!(".{}.{}.tmp", name, process::id()));
+        let result = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&tmp_path)
+            .and_then(|mut file| {
+                file.write_all(contents.as_bytes())?;
+                file.set_modified(SystemTime::from(clock.now()))?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&tmp_path, dir.join(name)));
+
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp_path);
+        }
+        result
+    }
+
+    /// Remove a cache file if it exists.
+    pub fn remove(&self, name: &str) -> io::Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        match fs::remove_file(dir.join(name)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// List all files in the cache directory.
+    pub fn entries(&self, clock: &dyn Clock) -> io::Result<Vec<CacheEntry>> {
+        let Some(dir) = &self.dir else {
+            return Ok(Vec::new());
+        };
+
+        let read_dir = match fs::read_dir(dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut entries = Vec::new();
+        for entry in read_dir {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+            entries.push(CacheEntry {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                size: metadata.len(),
+                age: age(&entry.path(), clock).unwrap_or_default(),
+            });
+        }
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    /// Remove cached data older than `max_age` and return how many files were removed.
+    /// State files are kept.
+    pub fn evict(&self, max_age: Duration, clock: &dyn Clock) -> io::Result<usize> {
+        let Some(dir) = &self.dir else {
+            return Ok(0);
+        };
+
+        let mut count = 0;
+        for entry in self.entries(clock)? {
+            let is_state = STATE_FILE_PREFIXES.iter().any(|prefix| entry.name.starts_with(prefix));
+            if entry.age > max_age && !is_state {
+                fs::remove_file(dir.join(&entry.name))?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Remove all files in the cache directory and return how many were removed.
+    pub fn clear(&self, clock: &dyn Clock) -> io::Result<usize> {
+        let Some(dir) = &self.dir else {
+            return Ok(0);
+        };
+
+        let entries = self.entries(clock)?;
+        for entry in &entries {
+            fs::remove_file(dir.join(&entry.name))?;
+        }
+        Ok(entries.len())
+    }
+}
+
+fn age(path: &Path, clock: &dyn Clock) -> Option<Duration> {
+    let modified = DateTime::<Utc>::from(fs::metadata(path).ok()?.modified().ok()?);
+    // A modification time in the future is treated as unknown age
+    (clock.now() - modified).to_std().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+
+    #[test]
+    fn evicts_only_files_older_than_max_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new("2024-06-01T12:00:00Z".parse().unwrap());
+
+        cache.write("old.json", "{}", &clock).unwrap();
+        cache.write("notifications.json", "{}", &clock).unwrap();
+        cache.write("backoff-met.json", "{}", &clock).unwrap();
+        cache.write("api-deprecated-met", "", &clock).unwrap();
+        clock.advance(chrono::Duration::hours(5));
+        cache.write("new.json", "{}", &clock).unwrap();
+        clock.advance(chrono::Duration::hours(2));
+
+        assert_eq!(cache.evict(Duration::from_secs(3 * 3600), &clock).unwrap(), 1);
+        let names: Vec<String> = cache.entries(&clock).unwrap().into_iter().map(|entry| entry.name).collect();
+        assert_eq!(names, ["api-deprecated-met", "backoff-met.json", "new.json", "notifications.json"]);
+    }
+}
@@ -0,0 +1,50 @@
+// Unicode block charts for the tooltip
+
+const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One block character per value, scaled so `max` is a full block.
+/// Values of zero or less are drawn as spaces, so dry periods stand out from light rain.
+pub fn sparkline(values: &[f64], max: f64) -> String {
+    values
+        .iter()
+        .map(|&value| {
+            if value <= 0.0 || max <= 0.0 {
+                return ' ';
+            }
+            let level = ((value / max) * BLOCKS.len() as f64).ceil() as usize;
+            BLOCKS[level.clamp(1, BLOCKS.len()) - 1]
+        })
+        .collect()
+}
+
+/// One block character per value, scaled so `min` is the lowest and `max` the highest block.
+/// Suited for values like temperature where zero has no special meaning.
+pub fn sparkline_range(values: &[f64], min: f64, max: f64) -> String {
+    values
+        .iter()
+        .map(|&value| {
+            if max <= min {
+                return BLOCKS[0];
+            }
+            let level = ((value - min) / (max - min) * (BLOCKS.len() - 1) as f64).round() as usize;
+            BLOCKS[level.min(BLOCKS.len() - 1)]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_values_to_blocks() {
+        assert_eq!(sparkline(&[0.0, 0.1, 1.0, 2.0, 4.0, 8.0, 16.0], 8.0), " ▁▁▂▄██");
+        assert_eq!(sparkline(&[1.0, 2.0], 0.0), "  ");
+    }
+
+    #[test]
+    fn scales_values_between_min_and_max() {
+        assert_eq!(sparkline_range(&[-7.0, 0.0, 3.5, 7.0], -7.0, 7.0), "▁▅▆█");
+        assert_eq!(sparkline_range(&[5.0, 5.0], 5.0, 5.0), "▁▁");
+    }
+}
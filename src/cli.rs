@@ -1,6 +1,10 @@
+use std::path::PathBuf;
+
 use crate::lang::Lang;
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
 
+/// Amount of detail in the bar text
 #[derive(Debug, Clone, ValueEnum)]
 pub enum IndicatorStyle {
     Concise,
@@ -8,6 +12,7 @@ pub enum IndicatorStyle {
     Full,
 }
 
+/// Forecast horizon shown in the tooltip
 #[derive(Debug, Clone, ValueEnum)]
 pub enum TooltipStyle {
     CurrentDay,
@@ -15,12 +20,54 @@ pub enum TooltipStyle {
     Week,
 }
 
+/// How the forecast in the tooltip is drawn
+#[derive(Debug, Clone, ValueEnum)]
+pub enum TooltipLayout {
+    /// Columns with an icon and temperature per entry
+    Grid,
+    /// Temperature and precipitation sparklines
+    Chart,
+}
+
+/// Source of the weather forecast
+#[derive(Debug, Clone, ValueEnum, Deserialize)]
+#[value(rename_all = "lower")]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    /// MET Norway, the default
+    Met,
+    /// Open-Meteo, combining national weather models
+    OpenMeteo,
+    /// OpenWeatherMap, which needs `openweathermap_api_key` in the config file
+    OpenWeatherMap,
+    /// Deutscher Wetterdienst through Bright Sky
+    Dwd,
+}
+
+/// Temperature unit
 #[derive(Debug, Clone, ValueEnum)]
 pub enum TempFormat {
     Celsius,
     Fahrenheit,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Inspect or clear cached location and forecast data
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheAction {
+    /// Remove all cached files
+    Clear,
+    /// Show the cache directory and its contents
+    Info,
+}
+
 #[derive(Parser, Debug)]
 #[command(
     author = "Endre Egset",
@@ -29,12 +76,40 @@ pub enum TempFormat {
     long_about = None
 )]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[arg(
+        long,
+        help = "Config file (default: $XDG_CONFIG_HOME/waybar-weather-info/config.toml)"
+    )]
+    pub config: Option<PathBuf>,
+
     #[arg(
         long,
-        help = "Location ID from yr.no (e.g. '1-72837') or geo-coordinates (e.g. '59.911561,10.7492741'). If not specified, uses IP-based geolocation"
+        help = "Contact email or URL sent in the User-Agent to api.met.no"
+    )]
+    pub contact: Option<String>,
+
+    #[arg(
+        long,
+        help = "Location ID from yr.no (e.g. '1-72837') or geo-coordinates with optional altitude in meters (e.g. '59.911561,10.7492741' or '61.6368,8.3124,900'). If not specified, uses GeoClue or IP-based geolocation"
     )]
     pub location: Option<String>,
 
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Weather provider (met, openmeteo, openweathermap, dwd), or a comma-separated list tried in order until one answers (default: met)"
+    )]
+    pub provider: Vec<Provider>,
+
+    #[arg(
+        long,
+        help = "Average the temperatures from all providers given with --provider and show their spread"
+    )]
+    pub ensemble: bool,
+
     #[arg(
         long,
         default_value = "concise",
@@ -42,6 +117,12 @@ pub struct Args {
     )]
     pub indicator_style: IndicatorStyle,
 
+    #[arg(
+        long,
+        help = "Indicator format replacing --indicator-style, with placeholders {icon}, {temp}, {wind}, {gust}, {precipitation}, {probability}, {humidity}, {uv}, {sunrise} and {sunset} (e.g. '{icon} {temp} 🌇{sunset}')"
+    )]
+    pub format: Option<String>,
+
     #[arg(
         long,
         default_value = "en",
@@ -56,10 +137,53 @@ pub struct Args {
     )]
     pub tooltip_style: TooltipStyle,
 
+    #[arg(
+        long,
+        default_value = "grid",
+        help = "Tooltip forecast layout (grid, chart)"
+    )]
+    pub tooltip_layout: TooltipLayout,
+
     #[arg(
         long,
         default_value = "celsius",
         help = "Temperature format (celsius, fahrenheit)"
     )]
     pub temp_format: TempFormat,
+
+    #[arg(
+        long,
+        help = "Interpolate current temperature, wind and humidity between the surrounding hourly forecasts"
+    )]
+    pub interpolate: bool,
+
+    #[arg(
+        long,
+        help = "Show when rain starts or stops from MET's radar nowcast (Nordic countries only)"
+    )]
+    pub nowcast: bool,
+
+    #[arg(
+        long,
+        help = "Show MET weather warnings for the location (Norway only)"
+    )]
+    pub alerts: bool,
+
+    #[arg(
+        long,
+        help = "Show the air quality index and pollutant levels from MET's air quality forecast (Norway only)"
+    )]
+    pub air_quality: bool,
+
+    #[arg(
+        long,
+        help = "Show the birch, grass and mugwort pollen forecast for today and tomorrow"
+    )]
+    pub pollen: bool,
+
+    #[arg(
+        long,
+        help = "Send desktop notifications when rain is about to start, the temperature crosses a configured threshold or a new weather warning is issued"
+    )]
+    pub notify: bool,
 }
@@ -0,0 +1,35 @@
+use std::cell::Cell;
+
+use chrono::{DateTime, Utc};
+
+/// Source of the current time, so time-dependent code can be tested deterministically.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock that stands still until it is moved explicitly.
+pub struct FixedClock(Cell<DateTime<Utc>>);
+
+impl FixedClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self(Cell::new(now))
+    }
+
+    pub fn advance(&self, duration: chrono::Duration) {
+        self.0.set(self.0.get() + duration);
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0.get()
+    }
+}
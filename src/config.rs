@@ -0,0 +1,139 @@
+// Optional settings file in $XDG_CONFIG_HOME/waybar-weather-info/config.toml
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::cli::Provider;
+use crate::error::{Error, Result};
+use crate::pollen::PollenLevel;
+
+const CONFIG_DIR_NAME: &str = "waybar-weather-info";
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Settings read from the config file. Command line options take precedence.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Location as 'lat,lon' or 'lat,lon,alt', used when `--location` is not given
+    pub location: Option<String>,
+    /// Weather providers in the order they are tried, used when `--provider` is not given
+    pub providers: Vec<Provider>,
+    /// Average the temperatures from all providers
+    pub ensemble: bool,
+    /// API key for the `openweathermap` provider
+    pub openweathermap_api_key: Option<String>,
+    /// Indicator format with placeholders, used when `--format` is not given
+    pub format: Option<String>,
+    /// Contact email or URL appended to the User-Agent, as MET Norway's terms of service ask for
+    pub contact: Option<String>,
+    /// Fetch the precipitation nowcast (Nordic countries only)
+    pub nowcast: bool,
+    /// Fetch weather warnings from MetAlerts (Norway only)
+    pub alerts: bool,
+    /// Fetch the air quality forecast (Norway only)
+    pub air_quality: bool,
+    /// Fetch the pollen forecast
+    pub pollen: bool,
+    /// JSON file to read the pollen forecast from instead of Open-Meteo
+    pub pollen_file: Option<PathBuf>,
+    /// Lowest pollen level today that shows a badge in the indicator
+    pub pollen_threshold: PollenLevel,
+    /// Chance of precipitation in the next hour, in %, from which the indicator gets the `rain` class
+    pub rain_probability: f64,
+    /// Gust speed in m/s from which the indicator gets the `windy` class
+    pub windy_gust: f64,
+    /// Gust speed in m/s from which the indicator gets the `storm` class instead of `windy`
+    pub storm_gust: f64,
+    /// Send desktop notifications about rain, temperature thresholds and new weather warnings
+    pub notify: bool,
+    /// Notify when the temperature is forecast to drop below this, in the unit of `--temp-format`
+    pub notify_temperature_below: Option<f64>,
+    /// Notify when the temperature is forecast to rise above this, in the unit of `--temp-format`
+    pub notify_temperature_above: Option<f64>,
+    /// Decimals kept in coordinates before requesting and caching forecasts
+    pub coordinate_precision: u32,
+    /// Cache files older than this many hours are deleted
+    pub cache_max_age_hours: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            location: None,
+            providers: Vec::new(),
+            ensemble: false,
+            openweathermap_api_key: None,
+            format: None,
+            contact: None,
+            nowcast: false,
+            alerts: false,
+            air_quality: false,
+            pollen: false,
+            pollen_file: None,
+            pollen_threshold: PollenLevel::Moderate,
+            rain_probability: 50.0,
+            // Near gale and storm on the Beaufort scale
+            windy_gust: 13.9,
+            storm_gust: 24.5,
+            notify: false,
+            notify_temperature_below: None,
+            notify_temperature_above: None,
+            // MET Norway asks for at most 4 decimals
+            coordinate_precision: 4,
+            cache_max_age_hours: 7 * 24,
+        }
+    }
+}
+
+impl Config {
+    /// Load the config file at `path`, or at the default location if `path` is `None`.
+    /// A missing file at the default location gives the default config.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !required => return Ok(Self::default()),
+            Err(e) => return Err(Error::Config(format!("{}: {}", path.display(), e))),
+        };
+        let config: Self = toml::from_str(&contents).map_err(|e| Error::Config(format!("{}: {}", path.display(), e)))?;
+        if config.windy_gust >= config.storm_gust {
+            return Err(Error::Config(format!("{}: windy_gust must be lower than storm_gust", path.display())));
+        }
+        Ok(config)
+    }
+}
+
+/// Default config file location, if `XDG_CONFIG_HOME` or `HOME` is set.
+pub fn default_path() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME").map(PathBuf::from) {
+        Some(dir) if dir.is_absolute() => dir,
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join(CONFIG_DIR_NAME).join(CONFIG_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_windy_gust_not_below_storm_gust() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        fs::write(&path, "windy_gust = 20\nstorm_gust = 15\n").unwrap();
+        assert!(matches!(Config::load(Some(&path)), Err(Error::Config(_))));
+
+        fs::write(&path, "windy_gust = 10\n").unwrap();
+        assert_eq!(Config::load(Some(&path)).unwrap().windy_gust, 10.0);
+    }
+}
@@ -1,45 +1,49 @@
 // Weather symbol codes from MET Norway
 // https://api.met.no/weatherapi/weathericon/2.0/documentation
-pub const WEATHER_SYMBOL_MAP: &[(&str, &str)] = &[
-    ("clearsky", "☀️"),
-    ("fair", "🌤️"),
-    ("partlycloudy", "⛅"),
-    ("cloudy", "☁️"),
-    ("lightrainshowers", "�️"),
-    ("rainshowers", "🌧️"),
-    ("heavyrainshowers", "🌧️"),
-    ("lightrain", "�️"),
-    ("rain", "🌧️"),
-    ("heavyrain", "�️"),
-    ("lightsleetshowers", "�️"),
-    ("sleetshowers", "�️"),
-    ("heavysleetshowers", "�️"),
-    ("lightsleet", "�️"),
-    ("sleet", "�️"),
-    ("heavysleet", "🌨️"),
-    ("lightsnowshowers", "🌨️"),
-    ("snowshowers", "❄️"),
-    ("heavysnowshowers", "❄️"),
-    ("lightsnow", "🌨️"),
-    ("snow", "❄️"),
-    ("heavysnow", "❄️"),
-    ("fog", "�️"),
-    ("lightrainshowersandthunder", "⛈️"),
-    ("rainshowersandthunder", "⛈️"),
-    ("heavyrainshowersandthunder", "⛈️"),
-    ("lightrainandthunder", "⛈️"),
-    ("rainandthunder", "⛈️"),
-    ("heavyrainandthunder", "⛈️"),
-    ("lightsleetshowersandthunder", "⛈️"),
-    ("sleetshowersandthunder", "⛈️"),
-    ("heavysleetshowersandthunder", "⛈️"),
-    ("lightsleetandthunder", "⛈️"),
-    ("sleetandthunder", "⛈️"),
-    ("heavysleetandthunder", "⛈️"),
-    ("lightsnowshowersandthunder", "⛈️"),
-    ("snowshowersandthunder", "⛈️"),
-    ("heavysnowshowersandthunder", "⛈️"),
-    ("lightsnowandthunder", "⛈️"),
-    ("snowandthunder", "⛈️"),
-    ("heavysnowandthunder", "⛈️"),
+
+/// Icons for each base symbol code as (code, day, night, polar twilight).
+/// MET only publishes `_day`, `_night` and `_polartwilight` variants of codes where
+/// the sky can be seen; the others use one icon throughout.
+pub const WEATHER_SYMBOL_MAP: &[(&str, &str, &str, &str)] = &[
+    ("clearsky", "☀️", "🌙", "🌅"),
+    ("fair", "🌤️", "🌛", "🌄"),
+    ("partlycloudy", "⛅", "☁️🌙", "☁️🌅"),
+    ("cloudy", "☁️", "☁️", "☁️"),
+    ("lightrainshowers", "🌦️", "🌧️🌙", "🌧️🌅"),
+    ("rainshowers", "🌧️", "🌧️🌙", "🌧️🌅"),
+    ("heavyrainshowers", "🌧️", "🌧️🌙", "🌧️🌅"),
+    ("lightrain", "🌧️", "🌧️", "🌧️"),
+    ("rain", "🌧️", "🌧️", "🌧️"),
+    ("heavyrain", "🌧️", "🌧️", "🌧️"),
+    ("lightsleetshowers", "🌨️", "🌨️🌙", "🌨️🌅"),
+    ("sleetshowers", "🌨️", "🌨️🌙", "🌨️🌅"),
+    ("heavysleetshowers", "🌨️", "🌨️🌙", "🌨️🌅"),
+    ("lightsleet", "🌨️", "🌨️", "🌨️"),
+    ("sleet", "🌨️", "🌨️", "🌨️"),
+    ("heavysleet", "🌨️", "🌨️", "🌨️"),
+    ("lightsnowshowers", "🌨️", "🌨️🌙", "🌨️🌅"),
+    ("snowshowers", "❄️", "❄️🌙", "❄️🌅"),
+    ("heavysnowshowers", "❄️", "❄️🌙", "❄️🌅"),
+    ("lightsnow", "🌨️", "🌨️", "🌨️"),
+    ("snow", "❄️", "❄️", "❄️"),
+    ("heavysnow", "❄️", "❄️", "❄️"),
+    ("fog", "🌫️", "🌫️", "🌫️"),
+    ("lightrainshowersandthunder", "⛈️", "⛈️🌙", "⛈️🌅"),
+    ("rainshowersandthunder", "⛈️", "⛈️🌙", "⛈️🌅"),
+    ("heavyrainshowersandthunder", "⛈️", "⛈️🌙", "⛈️🌅"),
+    ("lightrainandthunder", "⛈️", "⛈️", "⛈️"),
+    ("rainandthunder", "⛈️", "⛈️", "⛈️"),
+    ("heavyrainandthunder", "⛈️", "⛈️", "⛈️"),
+    ("lightsleetshowersandthunder", "⛈️", "⛈️🌙", "⛈️🌅"),
+    ("sleetshowersandthunder", "⛈️", "⛈️🌙", "⛈️🌅"),
+    ("heavysleetshowersandthunder", "⛈️", "⛈️🌙", "⛈️🌅"),
+    ("lightsleetandthunder", "⛈️", "⛈️", "⛈️"),
+    ("sleetandthunder", "⛈️", "⛈️", "⛈️"),
+    ("heavysleetandthunder", "⛈️", "⛈️", "⛈️"),
+    ("lightsnowshowersandthunder", "⛈️", "⛈️🌙", "⛈️🌅"),
+    ("snowshowersandthunder", "⛈️", "⛈️🌙", "⛈️🌅"),
+    ("heavysnowshowersandthunder", "⛈️", "⛈️🌙", "⛈️🌅"),
+    ("lightsnowandthunder", "⛈️", "⛈️", "⛈️"),
+    ("snowandthunder", "⛈️", "⛈️", "⛈️"),
+    ("heavysnowandthunder", "⛈️", "⛈️", "⛈️"),
 ];
@@ -0,0 +1,117 @@
+// MOSMIX forecast from Deutscher Wetterdienst, served as JSON by Bright Sky
+// https://brightsky.dev/docs/
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::forecast::{add_six_hour_totals, ForecastEntry, Period, WeatherProvider};
+use crate::location::Coordinates;
+
+/// How many days ahead to request; MOSMIX forecasts reach 10 days
+const FORECAST_DAYS: i64 = 7;
+
+/// DWD's forecast for the nearest MOSMIX station, best in Germany and neighbouring countries
+pub struct Dwd;
+
+impl WeatherProvider for Dwd {
+    fn name(&self) -> &'static str {
+        "dwd"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "DWD"
+    }
+
+    fn url(&self, coords: &Coordinates, now: DateTime<Utc>) -> String {
+        format!(
+            "https://api.brightsky.dev/weather?lat={}&lon={}&date={}&last_date={}&tz=Etc/UTC",
+            coords.lat,
+            coords.lon,
+            now.format("%Y-%m-%d"),
+            (now + chrono::Duration::days(FORECAST_DAYS)).format("%Y-%m-%d")
+        )
+    }
+
+    fn parse(&self, data: &Value) -> Result<Vec<ForecastEntry>> {
+        let weather = data["weather"]
+            .as_array()
+            .ok_or_else(|| Error::Parse("missing weather".to_string()))?;
+
+        let mut timeseries: Vec<ForecastEntry> = weather
+            .iter()
+            .enumerate()
+            .filter_map(|(i, record)| {
+                Some(ForecastEntry {
+                    time: record["timestamp"].as_str()?.parse::<DateTime<Utc>>().ok()?,
+                    temperature: record["temperature"].as_f64(),
+                    // Bright Sky gives wind speed in km/h
+                    wind_speed: record["wind_speed"].as_f64().map(|speed| speed / 3.6),
+                    wind_gust: record["wind_gust_speed"].as_f64().map(|speed| speed / 3.6),
+                    humidity: record["relative_humidity"].as_f64(),
+                    uv_index: None,
+                    temperature_range: None,
+                    next_hour: Some(Period {
+                        symbol_code: record["icon"].as_str().and_then(symbol_code).map(str::to_string),
+                        // Precipitation is summed over the hour before each timestamp
+                        precipitation: weather.get(i + 1).and_then(|next| next["precipitation"].as_f64()),
+                        precipitation_range: None,
+                        probability: weather.get(i + 1).and_then(|next| next["precipitation_probability"].as_f64()),
+                    }),
+                    next_6_hours: None,
+                })
+            })
+            .collect();
+        add_six_hour_totals(&mut timeseries);
+        Ok(timeseries)
+    }
+}
+
+/// MET symbol code for a Bright Sky icon
+fn symbol_code(icon: &str) -> Option<&'static str> {
+    Some(match icon {
+        "clear-day" => "clearsky_day",
+        "clear-night" => "clearsky_night",
+        "partly-cloudy-day" => "partlycloudy_day",
+        "partly-cloudy-night" => "partlycloudy_night",
+        "cloudy" | "wind" => "cloudy",
+        "fog" => "fog",
+        "rain" => "rain",
+        "sleet" | "hail" => "sleet",
+        "snow" => "snow",
+        "thunderstorm" => "rainandthunder",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BRIGHT_SKY_FIXTURE: &str = include_str!("../tests/fixtures/brightsky_weather.json");
+
+    #[test]
+    fn normalizes_mosmix_forecast() {
+        let data = serde_json::from_str(BRIGHT_SKY_FIXTURE).unwrap();
+        let timeseries = Dwd.parse(&data).unwrap();
+        assert_eq!(timeseries.len(), 10);
+
+        let first = &timeseries[0];
+        assert_eq!(first.time, "2024-06-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert_eq!(first.temperature, Some(21.3));
+        assert_eq!(first.wind_speed, Some(3.0));
+        assert_eq!(first.wind_gust, Some(7.0));
+        assert_eq!(first.next_hour.as_ref().unwrap().symbol_code.as_deref(), Some("clearsky_day"));
+
+        assert_eq!(timeseries[5].next_hour.as_ref().unwrap().precipitation, Some(0.4));
+        assert_eq!(timeseries[9].next_hour.as_ref().unwrap().symbol_code.as_deref(), Some("partlycloudy_night"));
+    }
+
+    #[test]
+    fn requests_a_week_from_today() {
+        let now = "2024-06-01T12:20:00Z".parse().unwrap();
+        assert_eq!(
+            Dwd.url(&Coordinates::new(52.52, 13.405), now),
+            "https://api.brightsky.dev/weather?lat=52.52&lon=13.405&date=2024-06-01&last_date=2024-06-08&tz=Etc/UTC"
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+use std::io;
+
+use chrono::{DateTime, Local, Utc};
+
+/// Everything that can make a run fail
+#[derive(Debug)]
+pub enum Error {
+    /// The request could not be sent or the response not received
+    Network(String),
+    /// The server answered with an unexpected HTTP status
+    HttpStatus(u16),
+    /// The server asked us to slow down; no requests are made until the given time
+    Throttled(DateTime<Utc>),
+    /// The response was not the expected JSON
+    Parse(String),
+    /// The location could not be determined
+    Location(String),
+    /// Reading or writing the cache failed
+    CacheIo(io::Error),
+    /// The config file could not be read or is invalid
+    Config(String),
+}
+
+impl Error {
+    /// Process exit code, distinct per kind of error so scripts can tell them apart.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Network(_) => 2,
+            Self::HttpStatus(_) => 3,
+            Self::Parse(_) => 4,
+            Self::Location(_) => 5,
+            Self::CacheIo(_) => 6,
+            Self::Throttled(_) => 7,
+            Self::Config(_) => 8,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network(e) => write!(f, "network error: {}", e),
+            Self::HttpStatus(403) => write!(f, "access forbidden (HTTP 403), the User-Agent may be blocked by the server"),
+            Self::HttpStatus(status) => write!(f, "unexpected HTTP status {}", status),
+            Self::Throttled(until) => write!(
+                f,
+                "rate limited by the server, retrying after {}",
+                until.with_timezone(&Local).format("%H:%M")
+            ),
+            Self::Parse(e) => write!(f, "invalid response: {}", e),
+            Self::Location(e) => write!(f, "location error: {}", e),
+            Self::CacheIo(e) => write!(f, "cache error: {}", e),
+            Self::Config(e) => write!(f, "config error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::CacheIo(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::CacheIo(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
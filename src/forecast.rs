@@ -0,0 +1,845 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::cache::Cache;
+use crate::clock::Clock;
+use crate::error::{Error, Result};
+use crate::http::Transport;
+use crate::location::Coordinates;
+
+const MIN_BACKOFF: Duration = Duration::from_secs(60);
+const MAX_BACKOFF: Duration = Duration::from_secs(3600);
+
+/// Clock, HTTP transport and cache used by the fetch and location code
+pub struct Context<'a> {
+    pub clock: &'a dyn Clock,
+    pub http: &'a dyn Transport,
+    pub cache: &'a Cache,
+}
+
+/// Source of weather forecasts, such as MET Norway or Open-Meteo
+pub trait WeatherProvider {
+    /// Short name used in cache file names, e.g. `met`
+    fn name(&self) -> &'static str;
+    /// Name shown in the tooltip, e.g. `MET Norway`
+    fn display_name(&self) -> &'static str;
+    /// URL of the forecast for a location, requested at `now`
+    fn url(&self, coords: &Coordinates, now: DateTime<Utc>) -> String;
+    /// Normalize a response into forecast entries, earliest first
+    fn parse(&self, data: &Value) -> Result<Vec<ForecastEntry>>;
+}
+
+/// Forecast for a point in time, normalized across providers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ForecastEntry {
+    pub time: DateTime<Utc>,
+    /// Air temperature in °C
+    pub temperature: Option<f64>,
+    /// Wind speed in m/s
+    pub wind_speed: Option<f64>,
+    /// Speed of wind gusts in m/s
+    pub wind_gust: Option<f64>,
+    /// Relative humidity in %
+    pub humidity: Option<f64>,
+    /// UV index for a clear sky
+    pub uv_index: Option<f64>,
+    /// Lowest and highest air temperature in °C among the providers of an ensemble forecast
+    pub temperature_range: Option<(f64, f64)>,
+    /// Weather in the hour from `time`
+    pub next_hour: Option<Period>,
+    /// Weather in the 6 hours from `time`
+    pub next_6_hours: Option<Period>,
+}
+
+/// Weather over a period following a forecast entry
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Period {
+    /// MET symbol code, e.g. `partlycloudy_day`, which is used for all providers
+    pub symbol_code: Option<String>,
+    /// Precipitation in mm
+    pub precipitation: Option<f64>,
+    /// Lowest and highest likely precipitation in mm
+    pub precipitation_range: Option<(f64, f64)>,
+    /// Chance of precipitation in %
+    pub probability: Option<f64>,
+}
+
+/// Forecast from a weather provider
+pub struct Forecast {
+    pub timeseries: Vec<ForecastEntry>,
+    /// Names of the providers the forecast comes from, more than one for an ensemble
+    pub providers: Vec<&'static str>,
+    /// Age of the cached data when it had to be used because fetching failed
+    pub stale_age: Option<Duration>,
+    /// The API answered 203 Non-Authoritative Information, meaning this version is deprecated
+    pub deprecated: bool,
+}
+
+/// Conditions at the current time
+pub struct CurrentWeather {
+    /// Air temperature in °C
+    pub temperature: f64,
+    /// Lowest and highest air temperature in °C among the providers of an ensemble forecast
+    pub temperature_range: Option<(f64, f64)>,
+    /// MET symbol code for the next hour, e.g. `partlycloudy_day`
+    pub symbol_code: String,
+    /// Wind speed in m/s
+    pub wind_speed: f64,
+    /// Speed of wind gusts in m/s, if the forecast has them
+    pub wind_gust: Option<f64>,
+    /// Relative humidity in %
+    pub humidity: f64,
+    /// Precipitation over the next hour in mm
+    pub precipitation: f64,
+    /// Lowest and highest likely precipitation over the next hour in mm, if the forecast has them
+    pub precipitation_range: Option<(f64, f64)>,
+    /// Chance of precipitation over the next hour in %, if the forecast has one
+    pub precipitation_probability: Option<f64>,
+    /// UV index for a clear sky, if the forecast has one
+    pub uv_index: Option<f64>,
+}
+
+/// Fetch the forecast for a location from `provider`, using the cache when it is fresh.
+/// If fetching fails, the newest cached forecast is returned as stale data if there is one.
+pub fn fetch_weather_data(ctx: &Context, provider: &dyn WeatherProvider, coords: &Coordinates) -> Result<Forecast> {
+    fetch_with_fallback(ctx, &[provider], coords)
+}
+
+/// Fetch the forecast from the first of `providers` that answers, so a provider that fails
+/// or is rate limited is replaced by the next. Only if all fail is a stale cached forecast
+/// returned, from the first provider that has one.
+pub fn fetch_with_fallback(ctx: &Context, providers: &[&dyn WeatherProvider], coords: &Coordinates) -> Result<Forecast> {
+    let mut first_error = None;
+    for provider in providers {
+        match fetch_fresh(ctx, *provider, coords) {
+            Ok(forecast) => return Ok(forecast),
+            Err(e) => {
+                if providers.len() > 1 {
+                    eprintln!("Warning: {} failed: {}", provider.display_name(), e);
+                }
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    let Some(error) = first_error else {
+        return Err(Error::Config("no weather provider selected".to_string()));
+    };
+    
+    // Fall back to the last good forecast, however old
+    for provider in providers {
+        if let Some(forecast) = fetch_stale(ctx, *provider, coords) {
+            let age = forecast.stale_age.unwrap_or_default();
+            eprintln!("Warning: {}. Using cached forecast from {} min ago.", error, age.as_secs() / 60);
+            return Ok(forecast);
+        }
+    }
+    Err(error)
+}
+
+/// Fetch the forecast from every provider and average the temperatures of those that answer.
+/// The timeseries is that of the first provider, with the spread between the providers
+/// in `temperature_range`. Other providers' temperatures are interpolated to its times,
+/// and stale forecasts are left out unless no provider answered.
+pub fn fetch_ensemble(ctx: &Context, providers: &[&dyn WeatherProvider], coords: &Coordinates) -> Result<Forecast> {
+    let mut members = Vec::new();
+    let mut first_error = None;
+    for provider in providers {
+        match fetch_weather_data(ctx, *provider, coords) {
+            Ok(forecast) => members.push(forecast),
+            Err(e) => {
+                eprintln!("Warning: {} failed: {}", provider.display_name(), e);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    
+    // A stale forecast would pull the average towards old data
+    let (members, stale): (Vec<Forecast>, Vec<Forecast>) = members.into_iter().partition(|member| member.stale_age.is_none());
+    let mut stale = stale.into_iter();
+    let mut members = members.into_iter();
+    let Some(mut forecast) = members.next().or_else(|| stale.next()) else {
+        return Err(first_error.unwrap_or_else(|| Error::Config("no weather provider selected".to_string())));
+    };
+    for member in stale {
+        eprintln!("Warning: Leaving {} out of the average, as its forecast is outdated", member.providers.join(", "));
+    }
+    let others: Vec<Forecast> = members.collect();
+    
+    for entry in &mut forecast.timeseries {
+        let temperatures: Vec<f64> = entry
+            .temperature
+            .into_iter()
+            .chain(others.iter().filter_map(|other| temperature_at(&other.timeseries, entry.time)))
+            .collect();
+        if temperatures.len() > 1 {
+            let min = temperatures.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = temperatures.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            entry.temperature = Some(temperatures.iter().sum::<f64>() / temperatures.len() as f64);
+            entry.temperature_range = Some((min, max));
+        }
+    }
+    for other in others {
+        forecast.providers.extend(other.providers);
+        forecast.stale_age = forecast.stale_age.max(other.stale_age);
+        forecast.deprecated |= other.deprecated;
+    }
+    Ok(forecast)
+}
+
+/// Temperature at `time`, interpolated between the surrounding entries.
+/// `None` outside the forecast, so members covering a shorter period are not extrapolated.
+fn temperature_at(timeseries: &[ForecastEntry], time: DateTime<Utc>) -> Option<f64> {
+    let after = timeseries.iter().position(|entry| entry.time >= time)?;
+    let next = &timeseries[after];
+    if next.time == time {
+        return next.temperature;
+    }
+    let previous = &timeseries[after.checked_sub(1)?];
+    let (start, end) = (previous.temperature?, next.temperature?);
+    Some(start + (end - start) * interpolation_fraction(previous.time, next.time, time))
+}
+
+fn cache_file(provider: &dyn WeatherProvider, coords: &Coordinates) -> String {
+    match coords.altitude {
+        Some(altitude) => format!("forecast-{}-{}-{}-{}.json", provider.name(), coords.lat, coords.lon, altitude),
+        None => format!("forecast-{}-{}-{}.json", provider.name(), coords.lat, coords.lon),
+    }
+}
+
+/// The forecast from the cache if it is fresh, or else from the provider.
+fn fetch_fresh(ctx: &Context, provider: &dyn WeatherProvider, coords: &Coordinates) -> Result<Forecast> {
+    const CACHE_DURATION: Duration = Duration::from_secs(900); // 15 minutes
+    let cache_file = cache_file(provider, coords);
+    let backoff_file = format!("backoff-{}.json", provider.name());
+    let deprecated_file = format!("api-deprecated-{}", provider.name());
+    let providers = vec![provider.display_name()];
+    
+    // Try to use cached data
+    if let Some(json_str) = ctx.cache.read(&cache_file, CACHE_DURATION, ctx.clock) {
+        if let Some(timeseries) = parse_cached(provider, &json_str) {
+            let deprecated = ctx.cache.read_with_age(&deprecated_file, ctx.clock).is_some();
+            return Ok(Forecast { timeseries, providers, stale_age: None, deprecated });
+        }
+    }
+    
+    let url = provider.url(coords, ctx.clock.now());
+    
+    let (timeseries, weather, deprecated) = match backoff_until(ctx, &backoff_file) {
+        Some(until) if until > ctx.clock.now() => return Err(Error::Throttled(until)),
+        _ => request_forecast(ctx, &url, &backoff_file)
+            .and_then(|(json, deprecated)| Ok((provider.parse(&json)?, json, deprecated)))?,
+    };
+    
+    // Remember the deprecation until a later response says otherwise
+    let _ = ctx.cache.remove(&backoff_file);
+    if deprecated {
+        let _ = ctx.cache.write(&deprecated_file, "", ctx.clock);
+    } else {
+        let _ = ctx.cache.remove(&deprecated_file);
+    }
+    
+    // Cache the response as it came, so it is parsed the same way when read back
+    if let Ok(json_str) = serde_json::to_string_pretty(&weather) {
+        let _ = ctx.cache.write(&cache_file, &json_str, ctx.clock);
+    }
+    
+    Ok(Forecast { timeseries, providers, stale_age: None, deprecated })
+}
+
+/// The newest cached forecast from the provider, however old.
+fn fetch_stale(ctx: &Context, provider: &dyn WeatherProvider, coords: &Coordinates) -> Option<Forecast> {
+    let (json_str, age) = ctx.cache.read_with_age(&cache_file(provider, coords), ctx.clock)?;
+    let timeseries = parse_cached(provider, &json_str)?;
+    let deprecated = ctx
+        .cache
+        .read_with_age(&format!("api-deprecated-{}", provider.name()), ctx.clock)
+        .is_some();
+    Some(Forecast { timeseries, providers: vec![provider.display_name()], stale_age: Some(age), deprecated })
+}
+
+fn parse_cached(provider: &dyn WeatherProvider, json_str: &str) -> Option<Vec<ForecastEntry>> {
+    let json = serde_json::from_str::<Value>(json_str).ok()?;
+    provider.parse(&json).ok()
+}
+
+/// Request the forecast and interpret the status code.
+/// Returns the forecast and whether the API version is deprecated.
+fn request_forecast(ctx: &Context, url: &str, backoff_file: &str) -> Result<(Value, bool)> {
+    let response = ctx.http.get(url)?;
+    
+    match response.status {
+        203 => {
+            eprintln!("Warning: This version of the weather API is deprecated (HTTP 203). Please update waybar-weather-info.");
+            Ok((response.json()?, true))
+        }
+        200..=299 => Ok((response.json()?, false)),
+        429 => {
+            let retry_after = response.retry_after(ctx.clock.now());
+            Err(Error::Throttled(record_throttling(ctx, backoff_file, retry_after)))
+        }
+        status => Err(Error::HttpStatus(status)),
+    }
+}
+
+/// End of the current backoff period after being throttled, if any.
+pub(crate) fn backoff_until(ctx: &Context, backoff_file: &str) -> Option<DateTime<Utc>> {
+    let (json_str, _) = ctx.cache.read_with_age(backoff_file, ctx.clock)?;
+    let json = serde_json::from_str::<Value>(&json_str).ok()?;
+    json["until"].as_str()?.parse().ok()
+}
+
+/// Persist an exponentially growing backoff period, at least as long as the server asked for.
+pub(crate) fn record_throttling(ctx: &Context, backoff_file: &str, retry_after: Option<Duration>) -> DateTime<Utc> {
+    let failures = ctx
+        .cache
+        .read_with_age(backoff_file, ctx.clock)
+        .and_then(|(json_str, _)| serde_json::from_str::<Value>(&json_str).ok())
+        .and_then(|json| json["failures"].as_u64())
+        .unwrap_or(0)
+        + 1;
+    
+    let exponential = MIN_BACKOFF.saturating_mul(1 << (failures - 1).min(16)).min(MAX_BACKOFF);
+    let backoff = exponential.max(retry_after.unwrap_or_default());
+    let until = ctx.clock.now() + backoff;
+    
+    let state = json!({
+        "until": until.to_rfc3339(),
+        "failures": failures,
+    });
+    let _ = ctx.cache.write(backoff_file, &state.to_string(), ctx.clock);
+    until
+}
+
+/// Fetch a JSON document for one of the sources besides the forecast, such as the nowcast.
+/// A cached copy younger than `max_age` is used as is. Otherwise the document is requested
+/// unless a backoff period is running, and cached if it could be parsed.
+pub(crate) fn fetch_json(ctx: &Context, url: &str, cache_file: &str, max_age: Duration, backoff_file: &str) -> Result<Value> {
+    if let Some(json_str) = ctx.cache.read(cache_file, max_age, ctx.clock) {
+        if let Ok(data) = serde_json::from_str::<Value>(&json_str) {
+            return Ok(data);
+        }
+    }
+    
+    if let Some(until) = backoff_until(ctx, backoff_file).filter(|until| *until > ctx.clock.now()) {
+        return Err(Error::Throttled(until));
+    }
+    
+    let response = ctx.http.get(url)?;
+    match response.status {
+        200..=299 => {
+            let data = response.json()?;
+            let _ = ctx.cache.remove(backoff_file);
+            let _ = ctx.cache.write(cache_file, &response.body, ctx.clock);
+            Ok(data)
+        }
+        429 => {
+            let retry_after = response.retry_after(ctx.clock.now());
+            Err(Error::Throttled(record_throttling(ctx, backoff_file, retry_after)))
+        }
+        status => Err(Error::HttpStatus(status)),
+    }
+}
+
+/// The newest cached copy of a JSON document, however old, together with its age.
+pub(crate) fn read_stale_json(ctx: &Context, cache_file: &str) -> Option<(Value, Duration)> {
+    let (json_str, age) = ctx.cache.read_with_age(cache_file, ctx.clock)?;
+    Some((serde_json::from_str(&json_str).ok()?, age))
+}
+
+/// Drop timeseries entries that lie entirely in the past, so the first entry covers `now`.
+pub fn skip_past_entries(timeseries: &mut Vec<ForecastEntry>, now: DateTime<Utc>) {
+    let current = timeseries.iter().rposition(|entry| entry.time <= now).unwrap_or(0);
+    timeseries.drain(..current);
+}
+
+/// Current conditions from the first timeseries entry, optionally interpolated
+/// towards the next entry according to `now`.
+pub fn extract_current_weather(timeseries: &[ForecastEntry], now: DateTime<Utc>, interpolate: bool) -> CurrentWeather {
+    let current = timeseries.first();
+    let next = timeseries.get(1);
+    let next_hour = current.and_then(|entry| entry.next_hour.as_ref());
+    
+    // Fraction of the way from the first entry towards the second
+    let fraction = match (current, next) {
+        (Some(current), Some(next)) if interpolate => interpolation_fraction(current.time, next.time, now),
+        _ => 0.0,
+    };
+    let instant_value = |field: fn(&ForecastEntry) -> Option<f64>| {
+        let value = current.and_then(field)?;
+        Some(match next.and_then(field) {
+            Some(next) => value + (next - value) * fraction,
+            None => value,
+        })
+    };
+    
+    CurrentWeather {
+        temperature: instant_value(|entry| entry.temperature).unwrap_or(0.0),
+        temperature_range: current.and_then(|entry| entry.temperature_range),
+        symbol_code: next_hour
+            .and_then(|period| period.symbol_code.clone())
+            .unwrap_or_else(|| "cloudy".to_string()),
+        wind_speed: instant_value(|entry| entry.wind_speed).unwrap_or(0.0),
+        wind_gust: instant_value(|entry| entry.wind_gust),
+        humidity: instant_value(|entry| entry.humidity).unwrap_or(0.0),
+        precipitation: next_hour
+            .and_then(|period| period.precipitation)
+            .unwrap_or(0.0),
+        precipitation_range: next_hour.and_then(|period| period.precipitation_range),
+        precipitation_probability: next_hour.and_then(|period| period.probability),
+        uv_index: instant_value(|entry| entry.uv_index),
+    }
+}
+
+fn interpolation_fraction(start: DateTime<Utc>, end: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    let span = (end - start).num_seconds();
+    if span <= 0 {
+        return 0.0;
+    }
+    ((now - start).num_seconds() as f64 / span as f64).clamp(0.0, 1.0)
+}
+
+/// Fill in the 6-hour periods of hourly entries from the precipitation of the following hours.
+/// Entries without 6 hours of hourly data after them get none.
+pub(crate) fn add_six_hour_totals(timeseries: &mut [ForecastEntry]) {
+    let hourly: Vec<Option<f64>> = timeseries
+        .iter()
+        .map(|entry| entry.next_hour.as_ref().and_then(|period| period.precipitation))
+        .collect();
+    for (i, entry) in timeseries.iter_mut().enumerate() {
+        let Some(hours) = hourly.get(i..i + 6) else {
+            continue;
+        };
+        if let Some(total) = hours.iter().copied().sum::<Option<f64>>() {
+            entry.next_6_hours = Some(Period { precipitation: Some(total), ..Default::default() });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{IndicatorStyle, TempFormat, TooltipLayout, TooltipStyle};
+    use crate::clock::FixedClock;
+    use crate::http::mock::FixtureTransport;
+    use crate::format;
+    use crate::lang::Lang;
+    use crate::met::Met;
+    use crate::openmeteo::OpenMeteo;
+    use crate::openweathermap::OpenWeatherMap;
+    use std::os::unix::fs::PermissionsExt;
+
+    const MET_FIXTURE: &str = include_str!("../tests/fixtures/met_locationforecast_complete.json");
+    const OSLO: Coordinates = Coordinates { lat: 59.9139, lon: 10.7522, altitude: None };
+    const MET_URL: &str = "https://api.met.no/weatherapi/locationforecast/2.0/complete";
+    const OPEN_METEO_FIXTURE: &str = include_str!("../tests/fixtures/openmeteo_forecast.json");
+    const OPEN_METEO_URL: &str = "https://api.open-meteo.com/v1/forecast";
+    const OPEN_WEATHER_MAP_FIXTURE: &str = include_str!("../tests/fixtures/openweathermap_forecast.json");
+
+    fn entry(time: &str, temperature: f64, wind_speed: f64) -> ForecastEntry {
+        ForecastEntry {
+            time: at(time),
+            temperature: Some(temperature),
+            wind_speed: Some(wind_speed),
+            humidity: Some(80.0),
+            next_hour: Some(Period { symbol_code: Some("rain".to_string()), precipitation: Some(0.4), ..Default::default() }),
+            ..Default::default()
+        }
+    }
+
+    fn forecast() -> Vec<ForecastEntry> {
+        vec![
+            entry("2024-06-01T10:00:00Z", 10.0, 2.0),
+            entry("2024-06-01T11:00:00Z", 12.0, 4.0),
+            entry("2024-06-01T12:00:00Z", 14.0, 6.0),
+            entry("2024-06-01T13:00:00Z", 18.0, 2.0),
+        ]
+    }
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    fn first_time(timeseries: &[ForecastEntry]) -> DateTime<Utc> {
+        timeseries[0].time
+    }
+
+    #[test]
+    fn skips_to_entry_bracketing_now() {
+        let mut data = forecast();
+        skip_past_entries(&mut data, at("2024-06-01T12:30:00Z"));
+        assert_eq!(first_time(&data), at("2024-06-01T12:00:00Z"));
+        assert_eq!(data.len(), 2);
+    }
+
+    #[test]
+    fn keeps_entry_starting_exactly_now() {
+        let mut data = forecast();
+        skip_past_entries(&mut data, at("2024-06-01T11:00:00Z"));
+        assert_eq!(first_time(&data), at("2024-06-01T11:00:00Z"));
+    }
+
+    #[test]
+    fn keeps_everything_before_first_entry() {
+        let mut data = forecast();
+        skip_past_entries(&mut data, at("2024-06-01T09:15:00Z"));
+        assert_eq!(first_time(&data), at("2024-06-01T10:00:00Z"));
+    }
+
+    #[test]
+    fn keeps_last_entry_when_forecast_has_expired() {
+        let mut data = forecast();
+        skip_past_entries(&mut data, at("2024-06-02T00:00:00Z"));
+        assert_eq!(first_time(&data), at("2024-06-01T13:00:00Z"));
+    }
+
+    #[test]
+    fn uses_entry_values_without_interpolation() {
+        let mut data = forecast();
+        let now = at("2024-06-01T12:30:00Z");
+        skip_past_entries(&mut data, now);
+        let current = extract_current_weather(&data, now, false);
+        assert_eq!(current.temperature, 14.0);
+        assert_eq!(current.wind_speed, 6.0);
+        assert_eq!(current.symbol_code, "rain");
+        assert_eq!(current.precipitation, 0.4);
+    }
+
+    #[test]
+    fn interpolates_between_surrounding_hours() {
+        let mut data = forecast();
+        let now = at("2024-06-01T12:15:00Z");
+        skip_past_entries(&mut data, now);
+        let current = extract_current_weather(&data, now, true);
+        assert_eq!(current.temperature, 15.0);
+        assert_eq!(current.wind_speed, 5.0);
+        assert_eq!(current.humidity, 80.0);
+    }
+
+    #[test]
+    fn interpolation_holds_last_entry() {
+        let mut data = forecast();
+        let now = at("2024-06-01T13:40:00Z");
+        skip_past_entries(&mut data, now);
+        let current = extract_current_weather(&data, now, true);
+        assert_eq!(current.temperature, 18.0);
+    }
+
+    fn met_transport() -> FixtureTransport {
+        FixtureTransport::default().route(MET_URL, 200, MET_FIXTURE)
+    }
+
+    #[test]
+    fn fetches_forecast_and_serves_it_from_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().join("cache"));
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = met_transport();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        let forecast = fetch_weather_data(&ctx, &Met, &OSLO).unwrap();
+        assert!(forecast.stale_age.is_none());
+        assert_eq!(first_time(&forecast.timeseries), at("2024-06-01T12:00:00Z"));
+        assert_eq!(
+            *http.requests.borrow(),
+            ["https://api.met.no/weatherapi/locationforecast/2.0/complete?lat=59.9139&lon=10.7522"]
+        );
+
+        clock.advance(chrono::Duration::minutes(10));
+        let cached = fetch_weather_data(&ctx, &Met, &OSLO).unwrap();
+        assert_eq!(cached.timeseries, forecast.timeseries);
+        assert_eq!(http.requests.borrow().len(), 1);
+
+        let mode = std::fs::metadata(dir.path().join("cache/forecast-met-59.9139-10.7522.json"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn requests_and_caches_forecast_per_altitude() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = met_transport();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        let cabin = Coordinates { altitude: Some(900), ..OSLO };
+        fetch_weather_data(&ctx, &Met, &cabin).unwrap();
+        fetch_weather_data(&ctx, &Met, &OSLO).unwrap();
+        fetch_weather_data(&ctx, &Met, &cabin).unwrap();
+        assert_eq!(
+            *http.requests.borrow(),
+            [
+                "https://api.met.no/weatherapi/locationforecast/2.0/complete?lat=59.9139&lon=10.7522&altitude=900",
+                "https://api.met.no/weatherapi/locationforecast/2.0/complete?lat=59.9139&lon=10.7522",
+            ]
+        );
+        assert!(dir.path().join("forecast-met-59.9139-10.7522-900.json").exists());
+    }
+
+    #[test]
+    fn caches_forecast_per_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = met_transport().route(OPEN_METEO_URL, 200, OPEN_METEO_FIXTURE);
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        let met = fetch_weather_data(&ctx, &Met, &OSLO).unwrap();
+        let open_meteo = fetch_weather_data(&ctx, &OpenMeteo, &OSLO).unwrap();
+        assert_ne!(met.timeseries, open_meteo.timeseries);
+        assert_eq!(fetch_weather_data(&ctx, &OpenMeteo, &OSLO).unwrap().timeseries, open_meteo.timeseries);
+        assert_eq!(http.requests.borrow().len(), 2);
+        assert!(dir.path().join("forecast-openmeteo-59.9139-10.7522.json").exists());
+    }
+
+    #[test]
+    fn falls_back_to_next_provider_when_throttled() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = FixtureTransport::default()
+            .route(MET_URL, 429, "")
+            .route(OPEN_METEO_URL, 200, OPEN_METEO_FIXTURE);
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        let forecast = fetch_with_fallback(&ctx, &[&Met, &OpenMeteo], &OSLO).unwrap();
+        assert_eq!(forecast.providers, ["Open-Meteo"]);
+        assert!(forecast.stale_age.is_none());
+
+        // MET is not asked again while backing off
+        clock.advance(chrono::Duration::seconds(30));
+        fetch_with_fallback(&ctx, &[&Met, &OpenMeteo], &OSLO).unwrap();
+        assert_eq!(http.requests.borrow().iter().filter(|url| url.starts_with(MET_URL)).count(), 1);
+    }
+
+    #[test]
+    fn uses_stale_forecast_only_when_all_providers_fail() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = FixtureTransport::default().route(OPEN_METEO_URL, 200, OPEN_METEO_FIXTURE);
+        fetch_weather_data(&Context { clock: &clock, http: &http, cache: &cache }, &OpenMeteo, &OSLO).unwrap();
+
+        clock.advance(chrono::Duration::hours(1));
+        let offline = FixtureTransport::default().route(MET_URL, 500, "");
+        let ctx = Context { clock: &clock, http: &offline, cache: &cache };
+        let forecast = fetch_with_fallback(&ctx, &[&Met, &OpenMeteo], &OSLO).unwrap();
+        assert_eq!(forecast.providers, ["Open-Meteo"]);
+        assert_eq!(forecast.stale_age, Some(Duration::from_secs(3600)));
+
+        let empty = tempfile::tempdir().unwrap();
+        let cache = Cache::new(empty.path().to_path_buf());
+        let ctx = Context { clock: &clock, http: &offline, cache: &cache };
+        let error = fetch_with_fallback(&ctx, &[&Met, &OpenMeteo], &OSLO).err().unwrap();
+        assert!(matches!(error, Error::HttpStatus(500)));
+    }
+
+    #[test]
+    fn averages_temperatures_in_ensemble() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = met_transport().route(OPEN_METEO_URL, 200, OPEN_METEO_FIXTURE);
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        let forecast = fetch_ensemble(&ctx, &[&Met, &OpenMeteo], &OSLO).unwrap();
+        assert_eq!(forecast.providers, ["MET Norway", "Open-Meteo"]);
+        let first = &forecast.timeseries[0];
+        assert!((first.temperature.unwrap() - 17.45).abs() < 1e-9);
+        assert_eq!(first.temperature_range, Some((17.4, 17.5)));
+        // Open-Meteo's fixture covers only 12 hours
+        assert_eq!(forecast.timeseries[20].temperature_range, None);
+
+        let current = extract_current_weather(&forecast.timeseries, clock.now(), false);
+        let tooltip = format::build_tooltip(&forecast.timeseries, &current, None, &Lang::EN, &TooltipStyle::CurrentDay, &TooltipLayout::Grid, &TempFormat::Celsius);
+        assert!(tooltip.contains("Temperature: 17°C (17°C–18°C)\n"));
+        assert_eq!(format::build_source_note(&forecast.providers, &Lang::EN), "\n<i>Source: MET Norway, Open-Meteo (average)</i>");
+    }
+
+    #[test]
+    fn interpolates_coarser_ensemble_members() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = met_transport().route("https://api.openweathermap.org/", 200, OPEN_WEATHER_MAP_FIXTURE);
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+        let open_weather_map = OpenWeatherMap::new("key".to_string());
+
+        let forecast = fetch_ensemble(&ctx, &[&Met, &open_weather_map], &OSLO).unwrap();
+        // OpenWeatherMap has 19.2°C at 12:00 and 20.1°C at 15:00, so 19.5°C at 13:00
+        let entry = &forecast.timeseries[1];
+        assert_eq!(entry.time, at("2024-06-01T13:00:00Z"));
+        assert!((entry.temperature.unwrap() - 18.95).abs() < 1e-9);
+        let (min, max) = entry.temperature_range.unwrap();
+        assert_eq!(min, 18.4);
+        assert!((max - 19.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn leaves_stale_members_out_of_ensemble() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = met_transport().route(OPEN_METEO_URL, 200, OPEN_METEO_FIXTURE);
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+        fetch_ensemble(&ctx, &[&Met, &OpenMeteo], &OSLO).unwrap();
+
+        clock.advance(chrono::Duration::minutes(20));
+        let failing = met_transport().route(OPEN_METEO_URL, 503, "");
+        let ctx = Context { clock: &clock, http: &failing, cache: &cache };
+        let forecast = fetch_ensemble(&ctx, &[&Met, &OpenMeteo], &OSLO).unwrap();
+        assert_eq!(forecast.providers, ["MET Norway"]);
+        assert_eq!(forecast.stale_age, None);
+        assert_eq!(forecast.timeseries[0].temperature, Some(17.5));
+        assert_eq!(forecast.timeseries[0].temperature_range, None);
+    }
+
+    #[test]
+    fn refetches_forecast_after_cache_expires() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = met_transport();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        fetch_weather_data(&ctx, &Met, &OSLO).unwrap();
+        clock.advance(chrono::Duration::minutes(16));
+        fetch_weather_data(&ctx, &Met, &OSLO).unwrap();
+        assert_eq!(http.requests.borrow().len(), 2);
+    }
+
+    #[test]
+    fn falls_back_to_stale_forecast_when_fetch_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = met_transport();
+        fetch_weather_data(&Context { clock: &clock, http: &http, cache: &cache }, &Met, &OSLO).unwrap();
+
+        clock.advance(chrono::Duration::hours(2));
+        let offline = FixtureTransport::default();
+        let forecast = fetch_weather_data(&Context { clock: &clock, http: &offline, cache: &cache }, &Met, &OSLO).unwrap();
+        assert_eq!(offline.requests.borrow().len(), 1);
+        assert_eq!(forecast.stale_age, Some(Duration::from_secs(2 * 3600)));
+    }
+
+    #[test]
+    fn reports_http_status_and_parse_errors_without_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+
+        let http = FixtureTransport::default().route(MET_URL, 500, "");
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+        let error = fetch_weather_data(&ctx, &Met, &OSLO).err().unwrap();
+        assert!(matches!(error, Error::HttpStatus(500)));
+        assert_eq!(error.exit_code(), 3);
+
+        let http = FixtureTransport::default().route(MET_URL, 200, "<html>");
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+        let error = fetch_weather_data(&ctx, &Met, &OSLO).err().unwrap();
+        assert!(matches!(error, Error::Parse(_)));
+    }
+
+    #[test]
+    fn backs_off_exponentially_when_throttled() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = FixtureTransport::default().route(MET_URL, 429, "");
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        let error = fetch_weather_data(&ctx, &Met, &OSLO).err().unwrap();
+        assert!(matches!(error, Error::Throttled(until) if until == at("2024-06-01T12:21:00Z")));
+
+        // No request is made while backing off
+        clock.advance(chrono::Duration::seconds(30));
+        assert!(matches!(fetch_weather_data(&ctx, &Met, &OSLO), Err(Error::Throttled(_))));
+        assert_eq!(http.requests.borrow().len(), 1);
+
+        clock.advance(chrono::Duration::seconds(30));
+        let error = fetch_weather_data(&ctx, &Met, &OSLO).err().unwrap();
+        assert!(matches!(error, Error::Throttled(until) if until == at("2024-06-01T12:23:00Z")));
+        assert_eq!(http.requests.borrow().len(), 2);
+    }
+
+    #[test]
+    fn honours_retry_after_and_resets_backoff_on_success() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let throttled = FixtureTransport::default().route_with_headers(MET_URL, 429, &[("Retry-After", "600")], "");
+        let ctx = Context { clock: &clock, http: &throttled, cache: &cache };
+
+        let error = fetch_weather_data(&ctx, &Met, &OSLO).err().unwrap();
+        assert!(matches!(error, Error::Throttled(until) if until == at("2024-06-01T12:30:00Z")));
+
+        clock.advance(chrono::Duration::minutes(10));
+        let http = met_transport();
+        fetch_weather_data(&Context { clock: &clock, http: &http, cache: &cache }, &Met, &OSLO).unwrap();
+        assert!(!dir.path().join("backoff-met.json").exists());
+    }
+
+    #[test]
+    fn reports_deprecated_api_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let http = FixtureTransport::default().route(MET_URL, 203, MET_FIXTURE);
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        assert!(fetch_weather_data(&ctx, &Met, &OSLO).unwrap().deprecated);
+        // Still reported while the forecast is served from cache
+        assert!(fetch_weather_data(&ctx, &Met, &OSLO).unwrap().deprecated);
+        assert_eq!(http.requests.borrow().len(), 1);
+    }
+
+    #[test]
+    fn formats_recorded_forecast_at_fixed_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T14:20:00Z"));
+        let http = met_transport();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        let mut forecast = fetch_weather_data(&ctx, &Met, &OSLO).unwrap();
+        skip_past_entries(&mut forecast.timeseries, clock.now());
+        let current = extract_current_weather(&forecast.timeseries, clock.now(), false);
+
+        assert_eq!(current.uv_index, Some(6.4));
+        let text = format::format_indicator(&current, &IndicatorStyle::Concise, &TempFormat::Celsius);
+        assert_eq!(text, "☀️ 19°C 💨4(8)m/s");
+
+        let tooltip = format::build_tooltip(&forecast.timeseries, &current, None, &Lang::EN, &TooltipStyle::CurrentDay, &TooltipLayout::Grid, &TempFormat::Celsius);
+        assert!(tooltip.starts_with("<b>Clear sky</b>\nTemperature: 19°C\nWind: Gentle breeze, 3.9 m/s (gusts 8.0 m/s)\n"));
+        assert!(tooltip.contains("15:00 🌤️ 19° 💨4(9)m/s"));
+    }
+
+    #[test]
+    fn draws_recorded_forecast_as_chart() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T14:20:00Z"));
+        let http = met_transport();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        let mut forecast = fetch_weather_data(&ctx, &Met, &OSLO).unwrap();
+        skip_past_entries(&mut forecast.timeseries, clock.now());
+        let current = extract_current_weather(&forecast.timeseries, clock.now(), false);
+
+        let tooltip = format::build_tooltip(&forecast.timeseries, &current, None, &Lang::EN, &TooltipStyle::CurrentDay, &TooltipLayout::Chart, &TempFormat::Celsius);
+        assert!(tooltip.ends_with(concat!(
+            "<b>Next hours:</b>\n",
+            "Temperature: 9°–19°\n",
+            "<tt>██ ██ ██ ▇▇ ▇▇ ▅▅ ▅▅ ▄▄ ▃▃ ▂▂ ▂▂ ▁▁</tt>\n",
+            "Precipitation: 0.7 mm\n",
+            "<tt>                           ▃▃ ▄▄ ▆▆</tt>\n",
+            "<tt>15 16 17 18 19 20 21 22 23 00 01 02</tt>\n",
+            "<tt>06-01                      06-02</tt>\n",
+        )));
+    }
+}
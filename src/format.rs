@@ -1,14 +1,27 @@
-use serde_json::Value;
-use crate::cli::{IndicatorStyle, TooltipStyle, TempFormat};
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::airquality::AirQuality;
+use crate::alerts::Alert;
+use crate::chart::{sparkline, sparkline_range};
+use crate::cli::{IndicatorStyle, TooltipLayout, TooltipStyle, TempFormat};
+use crate::config::Config;
 use crate::constants::WEATHER_SYMBOL_MAP;
+use crate::error::Error;
 use crate::lang::Lang;
-use crate::CurrentWeather;
+use crate::moon::MoonPhase;
+use crate::nowcast::{Nowcast, PrecipitationChange};
+use crate::pollen::{PollenDay, PollenType};
+use crate::sun::Daylight;
+use crate::uv::UvCategory;
+use crate::wind::{gust_class, Beaufort};
+use crate::forecast::{CurrentWeather, ForecastEntry};
 
 fn celsius_to_fahrenheit(celsius: f64) -> f64 {
     celsius * 9.0 / 5.0 + 32.0
 }
 
-fn format_temp(temp_c: f64, format: &TempFormat) -> String {
+pub(crate) fn format_temp(temp_c: f64, format: &TempFormat) -> String {
     match format {
         TempFormat::Celsius => format!("{}°C", temp_c.round() as i32),
         TempFormat::Fahrenheit => format!("{}°F", celsius_to_fahrenheit(temp_c).round() as i32),
@@ -22,10 +35,10 @@ fn format_temp_short(temp_c: f64, format: &TempFormat) -> i32 {
     }
 }
 
-fn get_symbol_from_data(data: &Value) -> &str {
-    data["next_1_hours"]["summary"]["symbol_code"]
-        .as_str()
-        .or_else(|| data["next_6_hours"]["summary"]["symbol_code"].as_str())
+fn get_symbol_from_entry(entry: &ForecastEntry) -> &str {
+    [&entry.next_hour, &entry.next_6_hours]
+        .into_iter()
+        .find_map(|period| period.as_ref()?.symbol_code.as_deref())
         .unwrap_or("cloudy")
 }
 
@@ -43,177 +56,596 @@ fn display_in_columns(tooltip: &mut String, entries: &[String], columns: usize,
     }
 }
 
+/// Text shown in the bar itself.
 pub fn format_indicator(current: &CurrentWeather, style: &IndicatorStyle, temp_format: &TempFormat) -> String {
     let icon = get_weather_icon(&current.symbol_code);
     let temp_str = format_temp(current.temperature, temp_format);
     
     match style {
         IndicatorStyle::Concise => {
-            format!("{} {} 💨{:.0}m/s", icon, temp_str, current.wind_speed)
+            format!("{} {} 💨{}", icon, temp_str, format_wind(current.wind_speed, current.wind_gust))
         }
         IndicatorStyle::Detailed => {
-            format!("{} {} 💧{:.1}mm 💨{:.0}m/s", 
-                icon, temp_str, current.precipitation, current.wind_speed)
+            format!("{} {} 💧{:.1}mm{} 💨{}", 
+                icon, temp_str, current.precipitation, format_probability_suffix(current), format_wind(current.wind_speed, current.wind_gust))
         }
         IndicatorStyle::Full => {
-            format!("{} {} 💧{:.1}mm 💨{:.0}m/s 💦{:.0}%", 
-                icon, temp_str, current.precipitation, current.wind_speed, current.humidity)
+            format!("{} {} 💧{:.1}mm{} 💨{} 💦{:.0}%", 
+                icon, temp_str, current.precipitation, format_probability_suffix(current), format_wind(current.wind_speed, current.wind_gust), current.humidity)
         }
     }
 }
 
-pub fn build_tooltip(data: &Value, lang: &Lang, style: &TooltipStyle, temp_format: &TempFormat) -> String {
+/// Mean wind with gusts in parentheses when the forecast has them, e.g. `4(9)m/s`
+fn format_wind(wind_speed: f64, wind_gust: Option<f64>) -> String {
+    match wind_gust {
+        Some(wind_gust) => format!("{:.0}({:.0})m/s", wind_speed, wind_gust),
+        None => format!("{:.0}m/s", wind_speed),
+    }
+}
+
+/// Chance of precipitation after the amount in the indicator, e.g. ` 70%`, if the forecast has one
+fn format_probability_suffix(current: &CurrentWeather) -> String {
+    current
+        .precipitation_probability
+        .map(|probability| format!(" {:.0}%", probability))
+        .unwrap_or_default()
+}
+
+/// Precipitation with its likely range and chance when the forecast has them, e.g. `0.2–1.8 mm (70%)`
+fn format_precipitation(amount: f64, range: Option<(f64, f64)>, probability: Option<f64>) -> String {
+    let mut text = match range {
+        Some((min, max)) if min < max => format!("{:.1}–{:.1} mm", min, max),
+        _ => format!("{:.1} mm", amount),
+    };
+    if let Some(probability) = probability {
+        text.push_str(&format!(" ({:.0}%)", probability));
+    }
+    text
+}
+
+/// Values for the placeholders of an indicator format, e.g. `{icon} {temp} {wind}`.
+pub fn indicator_placeholders(current: &CurrentWeather, temp_format: &TempFormat) -> Vec<(&'static str, String)> {
+    vec![
+        ("icon", get_weather_icon(&current.symbol_code).to_string()),
+        ("temp", format_temp(current.temperature, temp_format)),
+        ("wind", format!("{:.0}m/s", current.wind_speed)),
+        ("gust", current.wind_gust.map_or("–".to_string(), |wind_gust| format!("{:.0}m/s", wind_gust))),
+        ("precipitation", format!("{:.1}mm", current.precipitation)),
+        ("probability", current.precipitation_probability.map_or("–".to_string(), |probability| format!("{:.0}%", probability))),
+        ("humidity", format!("{:.0}%", current.humidity)),
+        ("uv", current.uv_index.map_or("–".to_string(), |uv_index| format!("{:.0}", uv_index))),
+    ]
+}
+
+/// Values for the `{sunrise}` and `{sunset}` placeholders in local time, `–` during polar day or night.
+pub fn sun_placeholders(daylight: &Daylight) -> Vec<(&'static str, String)> {
+    let (sunrise, sunset) = match daylight {
+        Daylight::Normal { sunrise, sunset, .. } => (format_local_time(*sunrise), format_local_time(*sunset)),
+        Daylight::PolarDay { .. } | Daylight::PolarNight { .. } => ("–".to_string(), "–".to_string()),
+    };
+    vec![("sunrise", sunrise), ("sunset", sunset)]
+}
+
+/// Replace `{name}` placeholders in `template`. Unknown placeholders are left as they are.
+pub fn render_template(template: &str, values: &[(&str, String)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+}
+
+/// Pango markup tooltip with current conditions and a forecast for the given style.
+pub fn build_tooltip(timeseries: &[ForecastEntry], current: &CurrentWeather, altitude: Option<i32>, lang: &Lang, style: &TooltipStyle, layout: &TooltipLayout, temp_format: &TempFormat) -> String {
     let mut tooltip = String::new();
     
     // Current weather
-    let current_data = &data["properties"]["timeseries"][0]["data"];
-    let instant = &current_data["instant"]["details"];
-    let next_1h = &current_data["next_1_hours"];
-    
-    let temp = instant["air_temperature"].as_f64().unwrap_or(0.0);
-    let temp_str = format_temp(temp, temp_format);
-    let symbol = next_1h["summary"]["symbol_code"].as_str().unwrap_or("cloudy");
-    let wind = instant["wind_speed"].as_f64().unwrap_or(0.0);
-    let humidity = instant["relative_humidity"].as_f64().unwrap_or(0.0);
-    let precip = next_1h["details"]["precipitation_amount"].as_f64().unwrap_or(0.0);
-    
-    tooltip.push_str(&format!("<b>{}</b>\n", symbol_to_description(symbol, lang)));
-    tooltip.push_str(&format!("{}: {}\n", lang.temperature(), temp_str));
-    tooltip.push_str(&format!("{}: {:.1} m/s\n", lang.wind(), wind));
-    tooltip.push_str(&format!("{}: {:.0}%\n", lang.humidity(), humidity));
-    tooltip.push_str(&format!("{}: {:.1} mm\n", lang.precipitation(), precip));
+    let temp_str = format_temp(current.temperature, temp_format);
+    
+    tooltip.push_str(&format!("<b>{}</b>\n", symbol_to_description(&current.symbol_code, lang)));
+    if let Some(altitude) = altitude {
+        tooltip.push_str(&format!("{}: {} m\n", lang.altitude(), altitude));
+    }
+    match current.temperature_range {
+        Some((min, max)) => tooltip.push_str(&format!(
+            "{}: {} ({}–{})\n",
+            lang.temperature(),
+            temp_str,
+            format_temp(min, temp_format),
+            format_temp(max, temp_format)
+        )),
+        None => tooltip.push_str(&format!("{}: {}\n", lang.temperature(), temp_str)),
+    }
+    let beaufort = lang.beaufort(&Beaufort::from_speed(current.wind_speed));
+    match current.wind_gust {
+        Some(wind_gust) => tooltip.push_str(&format!(
+            "{}: {}, {:.1} m/s ({} {:.1} m/s)\n",
+            lang.wind(),
+            beaufort,
+            current.wind_speed,
+            lang.gusts(),
+            wind_gust
+        )),
+        None => tooltip.push_str(&format!("{}: {}, {:.1} m/s\n", lang.wind(), beaufort, current.wind_speed)),
+    }
+    tooltip.push_str(&format!("{}: {:.0}%\n", lang.humidity(), current.humidity));
+    tooltip.push_str(&format!(
+        "{}: {}\n",
+        lang.precipitation(),
+        format_precipitation(current.precipitation, current.precipitation_range, current.precipitation_probability)
+    ));
     
     // Forecast based on tooltip style
-    match style {
-        TooltipStyle::CurrentDay => {
-            // Show rest of today (next 12 hours)
-            tooltip.push_str("\n<b>Next hours:</b>\n");
-            build_hourly_forecast(&mut tooltip, data, 12, temp_format);
-        }
-        TooltipStyle::ThreeDays => {
-            // Show next 72 hours (3 days at 3-hour intervals = 24 entries)
-            tooltip.push_str("\n<b>Next 3 days:</b>\n");
-            build_extended_forecast(&mut tooltip, data, 24, 3, temp_format);
+    let (heading, rows) = match style {
+        // Show rest of today (next 12 hours)
+        TooltipStyle::CurrentDay => ("Next hours:", forecast_rows(timeseries, 12, 1)),
+        // Show next 72 hours (3 days at 3-hour intervals = 24 entries)
+        TooltipStyle::ThreeDays => ("Next 3 days:", forecast_rows(timeseries, 24, 3)),
+        // Show next 168 hours (7 days at 6-hour intervals = 28 entries)
+        TooltipStyle::Week => ("Next week:", forecast_rows(timeseries, 28, 6)),
+    };
+    
+    tooltip.push_str(&format!("\n<b>{}</b>\n", heading));
+    match (layout, style) {
+        (TooltipLayout::Grid, TooltipStyle::CurrentDay) => build_hourly_forecast(&mut tooltip, &rows, temp_format),
+        (TooltipLayout::Grid, _) => build_extended_forecast(&mut tooltip, &rows, temp_format),
+        (TooltipLayout::Chart, _) => build_chart_forecast(&mut tooltip, &rows, lang, temp_format),
+    }
+    
+    tooltip
+}
+
+/// Indicator text telling when rain starts or stops, e.g. `☔ Rain in 12 min`.
+pub fn format_precipitation_change(change: &PrecipitationChange, lang: &Lang) -> String {
+    match change {
+        PrecipitationChange::StartsIn(minutes) => format!("☔ {} {} min", lang.rain_starts_in(), minutes),
+        PrecipitationChange::StopsIn(minutes) => format!("🌂 {} {} min", lang.rain_stops_in(), minutes),
+    }
+}
+
+/// Tooltip section with a sparkline of the precipitation rate for the next two hours.
+pub fn build_nowcast_tooltip(nowcast: &Nowcast, now: DateTime<Utc>, lang: &Lang) -> String {
+    // Scale to at least 2 mm/h, so drizzle does not look like a downpour
+    const MIN_SCALE: f64 = 2.0;
+    
+    let upcoming = nowcast.upcoming(now);
+    let rates: Vec<f64> = upcoming.iter().map(|(_, rate)| *rate).collect();
+    let max = rates.iter().copied().fold(MIN_SCALE, f64::max);
+    
+    let (Some((first, _)), Some((last, _))) = (upcoming.first(), upcoming.last()) else {
+        return String::new();
+    };
+    let start = first.with_timezone(&chrono::Local).format("%H:%M").to_string();
+    let end = last.with_timezone(&chrono::Local).format("%H:%M").to_string();
+    let padding = rates.len().saturating_sub(start.len() + end.len());
+    
+    format!(
+        "\n<b>{}:</b>\n<tt>{}</tt>\n<tt>{}{}{}</tt>\n",
+        lang.next_two_hours(),
+        sparkline(&rates, max),
+        start,
+        " ".repeat(padding),
+        end
+    )
+}
+
+/// Tooltip section listing weather warnings with their validity and instructions.
+pub fn build_alerts_tooltip(alerts: &[Alert], lang: &Lang) -> String {
+    let mut tooltip = format!("\n<b>⚠️ {}:</b>\n", lang.weather_alerts());
+    
+    for alert in alerts {
+        tooltip.push_str(&format!("<b>{}</b>\n{}\n", escape_markup(&alert.headline), format_alert_validity(alert)));
+        if let Some(instruction) = &alert.instruction {
+            tooltip.push_str(&format!("<i>{}</i>\n", escape_markup(instruction)));
         }
-        TooltipStyle::Week => {
-            // Show next 168 hours (7 days at 6-hour intervals = 28 entries)
-            tooltip.push_str("\n<b>Next week:</b>\n");
-            build_extended_forecast(&mut tooltip, data, 28, 6, temp_format);
+    }
+    
+    tooltip
+}
+
+/// Validity period of a warning in local time, e.g. `Sat 16:00 – Sun 00:00`.
+pub fn format_alert_validity(alert: &Alert) -> String {
+    let expires = alert.expires.with_timezone(&chrono::Local).format("%a %H:%M");
+    match alert.onset {
+        Some(onset) => format!("{} – {}", onset.with_timezone(&chrono::Local).format("%a %H:%M"), expires),
+        None => format!("– {}", expires),
+    }
+}
+
+/// Tooltip section with sunrise, sunset, solar noon and day length.
+pub fn build_sun_tooltip(daylight: &Daylight, lang: &Lang) -> String {
+    let mut tooltip = match daylight {
+        Daylight::Normal { sunrise, sunset, .. } => format!(
+            "\n🌅 {}: {}  🌇 {}: {}\n",
+            lang.sunrise(),
+            format_local_time(*sunrise),
+            lang.sunset(),
+            format_local_time(*sunset)
+        ),
+        Daylight::PolarDay { .. } => format!("\n☀️ {}\n", lang.midnight_sun()),
+        Daylight::PolarNight { .. } => format!("\n🌑 {}\n", lang.polar_night()),
+    };
+    
+    let minutes = daylight.day_length().num_minutes();
+    tooltip.push_str(&format!("{}: {}\n", lang.solar_noon(), format_local_time(daylight.solar_noon())));
+    tooltip.push_str(&format!("{}: {} h {} min\n", lang.day_length(), minutes / 60, minutes % 60));
+    tooltip
+}
+
+/// Tooltip section with the current and highest UV index today, and advice for the highest.
+pub fn build_uv_tooltip(uv_index: f64, max_today: f64, lang: &Lang) -> String {
+    format!(
+        "\n{}: {:.0} ({} {:.0})\n<i>{}</i>\n",
+        lang.uv_index(),
+        uv_index,
+        lang.max_today(),
+        max_today,
+        lang.uv_advice(&UvCategory::from_index(max_today))
+    )
+}
+
+/// Indicator badge with the air quality index, e.g. `AQI 2.3`.
+pub fn format_air_quality_badge(air_quality: &AirQuality) -> String {
+    format!("AQI {:.1}", air_quality.aqi)
+}
+
+/// Tooltip section with the air quality level and the concentration of each pollutant.
+pub fn build_air_quality_tooltip(air_quality: &AirQuality, lang: &Lang) -> String {
+    let mut tooltip = format!(
+        "\n<b>{}: {} ({:.1})</b>\n",
+        lang.air_quality(),
+        lang.aqi_level(&air_quality.level()),
+        air_quality.aqi
+    );
+    
+    let pollutants = [
+        ("PM2.5", air_quality.pm25),
+        ("PM10", air_quality.pm10),
+        ("NO₂", air_quality.no2),
+        ("O₃", air_quality.o3),
+    ];
+    for (name, concentration) in pollutants {
+        if let Some(concentration) = concentration {
+            tooltip.push_str(&format!("<tt>{:<6}{:>6.1} µg/m³</tt>\n", name, concentration));
         }
     }
     
     tooltip
 }
 
-fn build_hourly_forecast(tooltip: &mut String, data: &Value, hours: usize, temp_format: &TempFormat) {
-    let Some(timeseries) = data["properties"]["timeseries"].as_array() else {
-        return;
+/// Indicator badge with the pollen type of the highest level today, e.g. `🌼 Grass`.
+pub fn format_pollen_badge(pollen_type: PollenType, lang: &Lang) -> String {
+    format!("🌼 {}", lang.pollen_type(&pollen_type))
+}
+
+/// Tooltip section with the pollen level of each type today and tomorrow.
+pub fn build_pollen_tooltip(days: &[PollenDay], today: NaiveDate, lang: &Lang) -> String {
+    let find_day = |date: Option<NaiveDate>| days.iter().find(|day| Some(day.date) == date);
+    let columns = [find_day(Some(today)), find_day(today.succ_opt())];
+    let level = |day: Option<&PollenDay>, pollen_type| {
+        day.and_then(|day| day.level(pollen_type))
+            .map_or("–", |level| lang.pollen_level(&level))
     };
     
-    let mut entries = Vec::new();
+    let rows: Vec<String> = PollenType::ALL
+        .into_iter()
+        .filter(|pollen_type| columns.iter().any(|day| day.and_then(|day| day.level(*pollen_type)).is_some()))
+        .map(|pollen_type| {
+            format!(
+                "<tt>{:<10}{:<12}{}</tt>\n",
+                lang.pollen_type(&pollen_type),
+                level(columns[0], pollen_type),
+                level(columns[1], pollen_type)
+            )
+        })
+        .collect();
+    if rows.is_empty() {
+        return String::new();
+    }
     
-    for (i, entry) in timeseries.iter().take(hours + 1).enumerate() {
-        if i == 0 {
-            continue; // Skip current hour
+    format!(
+        "\n<b>{}</b>\n<tt>{:<10}{:<12}{}</tt>\n{}",
+        lang.pollen(),
+        "",
+        lang.today(),
+        lang.tomorrow(),
+        rows.concat()
+    )
+}
+
+/// Tooltip line with the moon phase and how much of the moon is lit, e.g. `🌔 Moon: 73%`.
+pub fn build_moon_tooltip(phase: &MoonPhase, lang: &Lang) -> String {
+    format!("{} {}: {:.0}%\n", phase.icon(), lang.moon(), phase.illumination() * 100.0)
+}
+
+fn format_local_time(time: DateTime<Utc>) -> String {
+    time.with_timezone(&chrono::Local).format("%H:%M").to_string()
+}
+
+/// Escape text for use in Pango markup.
+pub fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Tooltip explaining why no forecast could be shown.
+pub fn build_error_tooltip(error: &Error, lang: &Lang) -> String {
+    format!("<b>{}</b>\n{}", lang.error_message(error), escape_markup(&error.to_string()))
+}
+
+/// Tooltip note telling how old a stale forecast is.
+pub fn build_stale_note(age: Duration, lang: &Lang) -> String {
+    let minutes = age.as_secs() / 60;
+    let age_str = if minutes >= 60 {
+        format!("{} h {} min", minutes / 60, minutes % 60)
+    } else {
+        format!("{} min", minutes)
+    };
+    
+    format!("\n<i>{}: {}</i>", lang.outdated(), age_str)
+}
+
+/// Note naming the providers of the forecast, e.g. `Source: MET Norway, Open-Meteo (average)`.
+pub fn build_source_note(providers: &[&str], lang: &Lang) -> String {
+    let average = if providers.len() > 1 {
+        format!(" ({})", lang.average())
+    } else {
+        String::new()
+    };
+    format!("\n<i>{}: {}{}</i>", lang.source(), providers.join(", "), average)
+}
+
+/// A forecast entry shown in the tooltip
+struct ForecastRow<'a> {
+    time: DateTime<Utc>,
+    temp_c: f64,
+    symbol: &'a str,
+    /// Precipitation in mm for the period following `time`
+    precipitation: f64,
+    /// Wind speed in m/s
+    wind_speed: f64,
+    /// Speed of wind gusts in m/s
+    wind_gust: Option<f64>,
+}
+
+impl ForecastRow<'_> {
+    fn hour(&self) -> String {
+        self.time.format("%H").to_string()
+    }
+    
+    fn date(&self) -> String {
+        self.time.format("%m-%d").to_string()
+    }
+}
+
+/// Up to `max_entries` entries after the current one, at least `interval_hours` apart.
+fn forecast_rows(timeseries: &[ForecastEntry], max_entries: usize, interval_hours: usize) -> Vec<ForecastRow<'_>> {
+    // Get the first timestamp as our reference
+    let Some(start_time) = timeseries.first().map(|entry| entry.time) else {
+        return Vec::new();
+    };
+    
+    let mut rows = Vec::new();
+    let mut target_offset_hours = interval_hours as i64; // Start at first interval
+    
+    for (i, entry) in timeseries.iter().enumerate().skip(1) {
+        if rows.len() >= max_entries {
+            break;
         }
         
-        let time = entry["time"].as_str().unwrap_or("");
-        let hour = if time.len() >= 13 { &time[11..13] } else { "?" };
-        
-        let temp_c = entry["data"]["instant"]["details"]["air_temperature"]
-            .as_f64()
-            .unwrap_or(0.0);
-        let temp_display = format_temp_short(temp_c, temp_format);
+        if (entry.time - start_time).num_hours() < target_offset_hours {
+            continue;
+        }
         
-        let symbol = get_symbol_from_data(&entry["data"]);
-        let icon = get_weather_icon(symbol);
+        let precipitation = row_precipitation(&timeseries[i..], interval_hours);
         
-        entries.push(format!("{}:00 {} {}°", hour, icon, temp_display));
+        rows.push(ForecastRow {
+            time: entry.time,
+            temp_c: entry.temperature.unwrap_or(0.0),
+            symbol: get_symbol_from_entry(entry),
+            precipitation,
+            wind_speed: entry.wind_speed.unwrap_or(0.0),
+            wind_gust: entry.wind_gust,
+        });
+        target_offset_hours += interval_hours as i64;
     }
     
+    rows
+}
+
+/// Precipitation in mm over the `interval_hours` from the first entry. Hourly rows show
+/// the next hour and 6-hourly rows the next 6 hours. Rows in between add up the hours,
+/// or take their share of the 6-hour total where the forecast is no longer hourly.
+fn row_precipitation(entries: &[ForecastEntry], interval_hours: usize) -> f64 {
+    let Some(entry) = entries.first() else {
+        return 0.0;
+    };
+    let next_hour = entry.next_hour.as_ref().and_then(|period| period.precipitation);
+    let next_6_hours = entry.next_6_hours.as_ref().and_then(|period| period.precipitation);
+    
+    if interval_hours == 1 {
+        return next_hour.or(next_6_hours).unwrap_or(0.0);
+    }
+    if interval_hours >= 6 {
+        return next_6_hours.or(next_hour).unwrap_or(0.0);
+    }
+    
+    let end = entry.time + chrono::Duration::hours(interval_hours as i64);
+    let hours: Vec<&ForecastEntry> = entries.iter().take_while(|entry| entry.time < end).collect();
+    let hourly = hours
+        .iter()
+        .map(|entry| entry.next_hour.as_ref()?.precipitation)
+        .sum::<Option<f64>>()
+        .filter(|_| hours.len() == interval_hours);
+    hourly
+        .or_else(|| next_6_hours.map(|total| total * interval_hours as f64 / 6.0))
+        .or(next_hour)
+        .unwrap_or(0.0)
+}
+
+fn build_hourly_forecast(tooltip: &mut String, rows: &[ForecastRow], temp_format: &TempFormat) {
+    let entries: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            format!(
+                "{}:00 {} {}° 💨{}",
+                row.hour(),
+                get_weather_icon(row.symbol),
+                format_temp_short(row.temp_c, temp_format),
+                format_wind(row.wind_speed, row.wind_gust)
+            )
+        })
+        .collect();
+    
     // Display in 3 columns, sorted vertically
-    display_in_columns(tooltip, &entries, 3, 15);
+    display_in_columns(tooltip, &entries, 3, 24);
 }
 
-fn build_extended_forecast(tooltip: &mut String, data: &Value, max_entries: usize, interval_hours: usize, temp_format: &TempFormat) {
-    use chrono::{DateTime, Utc};
+fn build_extended_forecast(tooltip: &mut String, rows: &[ForecastRow], temp_format: &TempFormat) {
+    let entries: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            format!(
+                "{} {} {} {}°",
+                row.date(),
+                row.hour(),
+                get_weather_icon(row.symbol),
+                format_temp_short(row.temp_c, temp_format)
+            )
+        })
+        .collect();
     
-    if let Some(timeseries) = data["properties"]["timeseries"].as_array() {
-        if timeseries.is_empty() {
-            return;
-        }
-        
-        // Get the first timestamp as our reference
-        let first_time = timeseries[0]["time"].as_str().unwrap_or("");
-        let start_time = if let Ok(dt) = first_time.parse::<DateTime<Utc>>() {
-            dt
-        } else {
-            return;
-        };
-        
-        let mut entries = Vec::new();
-        let mut count = 0;
-        let mut target_offset_hours = interval_hours as i64; // Start at first interval
-        
-        for entry in timeseries.iter().skip(1) {
-            if count >= max_entries {
-                break;
-            }
-            
-            let time_str = entry["time"].as_str().unwrap_or("");
-            let entry_time = if let Ok(dt) = time_str.parse::<DateTime<Utc>>() {
-                dt
-            } else {
+    display_in_columns(tooltip, &entries, 2, 18);
+}
+
+/// Temperature and precipitation sparklines above a row of hour labels.
+/// Forecasts spanning several days get a row of dates below.
+fn build_chart_forecast(tooltip: &mut String, rows: &[ForecastRow], lang: &Lang, temp_format: &TempFormat) {
+    // Scale to at least 1 mm, so drizzle does not look like a downpour
+    const MIN_PRECIPITATION_SCALE: f64 = 1.0;
+    // Each entry gets a column of two blocks and a space, wide enough for the hour label
+    const COLUMN_WIDTH: usize = 3;
+    
+    if rows.is_empty() {
+        return;
+    }
+    
+    let temperatures: Vec<f64> = rows.iter().map(|row| format_temp_short(row.temp_c, temp_format) as f64).collect();
+    let min_temp = temperatures.iter().copied().fold(f64::INFINITY, f64::min);
+    let max_temp = temperatures.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let precipitation: Vec<f64> = rows.iter().map(|row| row.precipitation).collect();
+    let max_precipitation = precipitation.iter().copied().fold(0.0, f64::max);
+    
+    let widen = |line: String| -> String {
+        line.chars().map(|block| format!("{}{} ", block, block)).collect::<String>().trim_end().to_string()
+    };
+    
+    let hours: String = rows.iter().map(|row| format!("{:<COLUMN_WIDTH$}", row.hour())).collect();
+    
+    tooltip.push_str(&format!("{}: {}°–{}°\n", lang.temperature(), min_temp, max_temp));
+    tooltip.push_str(&format!("<tt>{}</tt>\n", widen(sparkline_range(&temperatures, min_temp, max_temp))));
+    tooltip.push_str(&format!("{}: {:.1} mm\n", lang.precipitation(), max_precipitation));
+    tooltip.push_str(&format!(
+        "<tt>{}</tt>\n",
+        widen(sparkline(&precipitation, max_precipitation.max(MIN_PRECIPITATION_SCALE)))
+    ));
+    tooltip.push_str(&format!("<tt>{}</tt>\n", hours.trim_end()));
+    
+    if rows.first().map(ForecastRow::date) != rows.last().map(ForecastRow::date) {
+        // Label the first column of each day, leaving out dates that would overlap the previous one
+        let mut dates = String::new();
+        let mut previous_date = String::new();
+        for (i, row) in rows.iter().enumerate() {
+            let column = i * COLUMN_WIDTH;
+            let date = row.date();
+            if date == previous_date {
                 continue;
-            };
-            
-            let hours_elapsed = (entry_time - start_time).num_hours();
-            
-            if hours_elapsed >= target_offset_hours {
-                let date_hour = if time_str.len() >= 16 {
-                    format!("{} {}", &time_str[5..10], &time_str[11..13])
-                } else {
-                    "?".to_string()
-                };
-                
-                let temp_c = entry["data"]["instant"]["details"]["air_temperature"]
-                    .as_f64()
-                    .unwrap_or(0.0);
-                let temp_display = format_temp_short(temp_c, temp_format);
-                
-                let symbol = get_symbol_from_data(&entry["data"]);
-                let icon = get_weather_icon(symbol);
-                
-                entries.push(format!("{} {} {}°", date_hour, icon, temp_display));
-                
-                count += 1;
-                target_offset_hours += interval_hours as i64;
             }
+            if dates.len() <= column {
+                dates.push_str(&" ".repeat(column - dates.len()));
+                dates.push_str(&date);
+            }
+            previous_date = date;
         }
-        
-        display_in_columns(tooltip, &entries, 2, 18);
+        tooltip.push_str(&format!("<tt>{}</tt>\n", dates));
     }
 }
 
+/// Time of day a MET symbol code is drawn for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolVariant {
+    Day,
+    Night,
+    PolarTwilight,
+}
+
+impl SymbolVariant {
+    /// CSS class for the indicator
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Night => "night",
+            Self::PolarTwilight => "polartwilight",
+        }
+    }
+}
+
+/// Split a symbol code like `fair_night` into its base code and variant.
+pub fn split_symbol(symbol_code: &str) -> (&str, Option<SymbolVariant>) {
+    [
+        ("_day", SymbolVariant::Day),
+        ("_night", SymbolVariant::Night),
+        ("_polartwilight", SymbolVariant::PolarTwilight),
+    ]
+    .into_iter()
+    .find_map(|(suffix, variant)| Some((symbol_code.strip_suffix(suffix)?, Some(variant))))
+    .unwrap_or((symbol_code, None))
+}
+
+/// Symbol code for a base code at a time of day, e.g. `fair_night` for `fair`.
+/// Codes where the sky cannot be seen, like `rain`, have no variants and are returned as they are.
+pub fn with_variant(base_symbol: &str, variant: SymbolVariant) -> String {
+    if !matches!(base_symbol, "clearsky" | "fair" | "partlycloudy") && !base_symbol.contains("showers") {
+        return base_symbol.to_string();
+    }
+    let suffix = match variant {
+        SymbolVariant::Day => "day",
+        SymbolVariant::Night => "night",
+        SymbolVariant::PolarTwilight => "polartwilight",
+    };
+    format!("{}_{}", base_symbol, suffix)
+}
+
+/// Emoji for a MET symbol code, with a moon at night and a low sun in polar twilight.
 pub fn get_weather_icon(symbol_code: &str) -> &str {
-    // Remove _day/_night/_polar suffix if present
-    let base_symbol = symbol_code
-        .trim_end_matches("_day")
-        .trim_end_matches("_night")
-        .trim_end_matches("_polartwilight");
+    let (base_symbol, variant) = split_symbol(symbol_code);
     
     WEATHER_SYMBOL_MAP
         .iter()
-        .find(|(code, _)| *code == base_symbol)
-        .map(|(_, icon)| *icon)
+        .find(|(code, ..)| *code == base_symbol)
+        .map(|(_, day, night, polar_twilight)| match variant {
+            Some(SymbolVariant::Night) => *night,
+            Some(SymbolVariant::PolarTwilight) => *polar_twilight,
+            Some(SymbolVariant::Day) | None => *day,
+        })
         .unwrap_or("🌡️")
 }
 
+/// CSS classes for the current weather: its symbol and time of day, `rain` when
+/// precipitation is likely even if the symbol shows none, and `windy` or `storm` for gusts.
+pub fn weather_classes<'a>(current: &'a CurrentWeather, config: &Config) -> Vec<&'a str> {
+    let mut classes = vec![get_weather_class(&current.symbol_code)];
+    if let (_, Some(variant)) = split_symbol(&current.symbol_code) {
+        classes.push(variant.class());
+    }
+    if current.precipitation_probability.is_some_and(|probability| probability >= config.rain_probability) && !classes.contains(&"rain") {
+        classes.push("rain");
+    }
+    classes.extend(current.wind_gust.and_then(|wind_gust| gust_class(wind_gust, config)));
+    classes
+}
+
+/// CSS class for a MET symbol code.
 pub fn get_weather_class(symbol_code: &str) -> &str {
-    let base = symbol_code
-        .trim_end_matches("_day")
-        .trim_end_matches("_night")
-        .trim_end_matches("_polartwilight");
+    let (base, _) = split_symbol(symbol_code);
     
     if base.contains("clearsky") {
         "clear"
@@ -237,3 +669,119 @@ pub fn get_weather_class(symbol_code: &str) -> &str {
 fn symbol_to_description<'a>(symbol: &str, lang: &'a Lang) -> &'a str {
     lang.weather_desc(symbol)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forecast::Period;
+    use crate::pollen::PollenLevel;
+
+    #[test]
+    fn renders_indicator_template() {
+        let values = [("icon", "🌙".to_string()), ("temp", "-3°C".to_string()), ("sunrise", "08:18".to_string())];
+        assert_eq!(render_template("{icon} {temp} 🌅{sunrise}", &values), "🌙 -3°C 🌅08:18");
+        assert_eq!(render_template("{temp} {unknown}", &values), "-3°C {unknown}");
+    }
+
+    #[test]
+    fn sums_precipitation_over_row_interval() {
+        let entry = |time: &str, next_hour: Option<f64>, next_6_hours: f64| ForecastEntry {
+            time: time.parse().unwrap(),
+            next_hour: next_hour.map(|precipitation| Period { precipitation: Some(precipitation), ..Default::default() }),
+            next_6_hours: Some(Period { precipitation: Some(next_6_hours), ..Default::default() }),
+            ..Default::default()
+        };
+        let hourly = [
+            entry("2024-06-02T00:00:00Z", Some(0.25), 3.0),
+            entry("2024-06-02T01:00:00Z", Some(0.5), 3.0),
+            entry("2024-06-02T02:00:00Z", Some(0.75), 3.0),
+        ];
+        assert_eq!(row_precipitation(&hourly, 1), 0.25);
+        assert_eq!(row_precipitation(&hourly, 3), 1.5);
+        assert_eq!(row_precipitation(&hourly, 6), 3.0);
+
+        let six_hourly = [entry("2024-06-05T00:00:00Z", None, 1.2), entry("2024-06-05T06:00:00Z", None, 0.0)];
+        assert!((row_precipitation(&six_hourly, 3) - 0.6).abs() < 1e-9);
+        assert_eq!(row_precipitation(&six_hourly, 6), 1.2);
+    }
+
+    #[test]
+    fn adds_classes_for_likely_rain_and_gusts() {
+        let current = CurrentWeather {
+            temperature: 12.0,
+            temperature_range: None,
+            symbol_code: "partlycloudy_day".to_string(),
+            wind_speed: 9.0,
+            wind_gust: Some(16.0),
+            humidity: 70.0,
+            precipitation: 0.2,
+            precipitation_range: Some((0.0, 0.8)),
+            precipitation_probability: Some(60.0),
+            uv_index: None,
+        };
+        let config = Config::default();
+        assert_eq!(weather_classes(&current, &config), ["cloudy", "day", "rain", "windy"]);
+
+        let config = Config { rain_probability: 70.0, storm_gust: 15.0, ..Config::default() };
+        assert_eq!(weather_classes(&current, &config), ["cloudy", "day", "storm"]);
+    }
+
+    #[test]
+    fn shows_precipitation_range_and_probability() {
+        assert_eq!(format_precipitation(0.6, Some((0.2, 1.8)), Some(70.0)), "0.2–1.8 mm (70%)");
+        assert_eq!(format_precipitation(0.0, Some((0.0, 0.0)), Some(3.0)), "0.0 mm (3%)");
+        assert_eq!(format_precipitation(0.5, None, None), "0.5 mm");
+    }
+
+    #[test]
+    fn advises_on_sun_protection_for_daily_maximum() {
+        assert_eq!(
+            build_uv_tooltip(2.0, 6.4, &Lang::EN),
+            "\nUV index: 2 (max today 6)\n<i>Seek shade at midday, wear a hat and sunscreen</i>\n"
+        );
+    }
+
+    #[test]
+    fn lists_pollutants_in_air_quality_tooltip() {
+        let air_quality = AirQuality { aqi: 2.3, pm25: Some(10.9), pm10: Some(19.5), no2: None, o3: Some(86.0) };
+        assert_eq!(format_air_quality_badge(&air_quality), "AQI 2.3");
+        assert_eq!(
+            build_air_quality_tooltip(&air_quality, &Lang::EN),
+            "\n<b>Air quality: Moderate (2.3)</b>\n<tt>PM2.5   10.9 µg/m³</tt>\n<tt>PM10    19.5 µg/m³</tt>\n<tt>O₃      86.0 µg/m³</tt>\n"
+        );
+    }
+
+    #[test]
+    fn shows_pollen_levels_for_today_and_tomorrow() {
+        let date = |date: &str| date.parse::<NaiveDate>().unwrap();
+        let days = [
+            PollenDay { date: date("2024-06-01"), birch: Some(PollenLevel::Low), grass: Some(PollenLevel::High), mugwort: None },
+            PollenDay { date: date("2024-06-02"), birch: None, grass: Some(PollenLevel::Moderate), mugwort: None },
+        ];
+        assert_eq!(format_pollen_badge(PollenType::Grass, &Lang::EN), "🌼 Grass");
+        assert_eq!(
+            build_pollen_tooltip(&days, date("2024-06-01"), &Lang::EN),
+            "\n<b>Pollen</b>\n<tt>          Today       Tomorrow</tt>\n<tt>Birch     Low         –</tt>\n<tt>Grass     High        Moderate</tt>\n"
+        );
+    }
+
+    #[test]
+    fn picks_icon_for_time_of_day() {
+        assert_eq!(get_weather_icon("clearsky_day"), "☀️");
+        assert_eq!(get_weather_icon("clearsky_night"), "🌙");
+        assert_eq!(get_weather_icon("clearsky_polartwilight"), "🌅");
+        assert_eq!(get_weather_icon("fair_night"), "🌛");
+        assert_eq!(get_weather_icon("rainshowersandthunder_night"), "⛈️🌙");
+        assert_eq!(get_weather_icon("heavyrain"), "🌧️");
+        assert_eq!(get_weather_icon("unknown_day"), "🌡️");
+    }
+
+    #[test]
+    fn splits_symbol_variant() {
+        assert_eq!(split_symbol("partlycloudy_polartwilight"), ("partlycloudy", Some(SymbolVariant::PolarTwilight)));
+        assert_eq!(split_symbol("fog"), ("fog", None));
+        assert_eq!(get_weather_class("lightsnowshowers_night"), "snow");
+        assert_eq!(with_variant("lightsnowshowers", SymbolVariant::Night), "lightsnowshowers_night");
+        assert_eq!(with_variant("fog", SymbolVariant::Day), "fog");
+    }
+}
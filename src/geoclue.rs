@@ -0,0 +1,261 @@
+// Location lookup through GeoClue2 on the system D-Bus
+// https://www.freedesktop.org/software/geoclue/docs/
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use zbus::blocking::Connection;
+use zbus::proxy;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::OwnedObjectPath;
+
+use crate::forecast::Context;
+
+const DESKTOP_ID: &str = "waybar-weather-info";
+const CACHE_FILE: &str = "location-geoclue.json";
+const CACHE_DURATION: Duration = Duration::from_secs(3600); // 1 hour
+const ACCURACY_LEVEL_CITY: u32 = 4; // GClueAccuracyLevel::CITY
+const LOCATION_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[proxy(
+    interface = "org.freedesktop.GeoClue2.Manager",
+    default_service = "org.freedesktop.GeoClue2",
+    default_path = "/org/freedesktop/GeoClue2/Manager"
+)]
+trait Manager {
+    fn get_client(&self) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.GeoClue2.Client",
+    default_service = "org.freedesktop.GeoClue2"
+)]
+trait Client {
+    fn start(&self) -> zbus::Result<()>;
+    fn stop(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn location(&self) -> zbus::Result<OwnedObjectPath>;
+    #[zbus(property)]
+    fn set_desktop_id(&self, id: &str) -> zbus::Result<()>;
+    #[zbus(property)]
+    fn set_requested_accuracy_level(&self, level: u32) -> zbus::Result<()>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.GeoClue2.Location",
+    default_service = "org.freedesktop.GeoClue2"
+)]
+trait Location {
+    #[zbus(property)]
+    fn latitude(&self) -> zbus::Result<f64>;
+    #[zbus(property)]
+    fn longitude(&self) -> zbus::Result<f64>;
+}
+
+/// Ask GeoClue on the system bus for the current location, cached for an hour.
+/// Returns `None` if GeoClue is unavailable, denies access or has no fix in time.
+pub fn get_location(ctx: &Context) -> Option<(f64, f64)> {
+    if let Some(cache_content) = ctx.cache.read(CACHE_FILE, CACHE_DURATION, ctx.clock) {
+        if let Ok(json) = serde_json::from_str::<Value>(&cache_content) {
+            if let (Some(lat), Some(lon)) = (json["latitude"].as_f64(), json["longitude"].as_f64()) {
+                return Some((lat, lon));
+            }
+        }
+    }
+
+    let conn = Connection::system().ok()?;
+    locate_and_cache(ctx, &conn, LOCATION_TIMEOUT)
+}
+
+fn locate_and_cache(ctx: &Context, conn: &Connection, timeout: Duration) -> Option<(f64, f64)> {
+    match locate(conn, timeout) {
+        Ok(Some((lat, lon))) => {
+            let cache_data = serde_json::json!({
+                "latitude": lat,
+                "longitude": lon,
+            });
+            let _ = ctx.cache.write(CACHE_FILE, &cache_data.to_string(), ctx.clock);
+            Some((lat, lon))
+        }
+        Ok(None) => None,
+        Err(e) => {
+            eprintln!("Warning: GeoClue location lookup failed: {}", e);
+            None
+        }
+    }
+}
+
+fn locate(conn: &Connection, timeout: Duration) -> zbus::Result<Option<(f64, f64)>> {
+    let manager = ManagerProxyBlocking::new(conn)?;
+    let client_path = manager.get_client()?;
+    let client = ClientProxyBlocking::builder(conn)
+        .path(client_path)?
+        .cache_properties(CacheProperties::No)
+        .build()?;
+
+    client.set_desktop_id(DESKTOP_ID)?;
+    client.set_requested_accuracy_level(ACCURACY_LEVEL_CITY)?;
+    client.start()?;
+
+    let result = wait_for_location(conn, &client, timeout);
+    let _ = client.stop();
+    result
+}
+
+fn wait_for_location(
+    conn: &Connection,
+    client: &ClientProxyBlocking,
+    timeout: Duration,
+) -> zbus::Result<Option<(f64, f64)>> {
+    let deadline = Instant::now() + timeout;
+
+    // The client exposes "/" as its location until GeoClue has a fix
+    loop {
+        let path = client.location()?;
+        if path.as_str() != "/" {
+            let location = LocationProxyBlocking::builder(conn)
+                .path(path)?
+                .cache_properties(CacheProperties::No)
+                .build()?;
+            return Ok(Some((location.latitude()?, location.longitude()?)));
+        }
+
+        if Instant::now() >= deadline {
+            return Ok(None);
+        }
+        sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use crate::cache::Cache;
+    use crate::clock::FixedClock;
+    use crate::http::mock::FixtureTransport;
+    use crate::testbus::PrivateBus;
+    use zbus::interface;
+    use zbus::zvariant::ObjectPath;
+
+    const CLIENT_PATH: &str = "/org/freedesktop/GeoClue2/Client/1";
+    const LOCATION_PATH: &str = "/org/freedesktop/GeoClue2/Location/1";
+
+    struct MockManager;
+
+    #[interface(name = "org.freedesktop.GeoClue2.Manager")]
+    impl MockManager {
+        fn get_client(&self) -> OwnedObjectPath {
+            ObjectPath::from_static_str_unchecked(CLIENT_PATH).into()
+        }
+    }
+
+    struct MockClient {
+        started: Arc<Mutex<bool>>,
+        desktop_id: String,
+        accuracy: u32,
+    }
+
+    #[interface(name = "org.freedesktop.GeoClue2.Client")]
+    impl MockClient {
+        fn start(&self) {
+            *self.started.lock().unwrap() = true;
+        }
+
+        fn stop(&self) {
+            *self.started.lock().unwrap() = false;
+        }
+
+        #[zbus(property)]
+        fn location(&self) -> OwnedObjectPath {
+            let path = if *self.started.lock().unwrap() { LOCATION_PATH } else { "/" };
+            ObjectPath::from_static_str_unchecked(path).into()
+        }
+
+        #[zbus(property)]
+        fn desktop_id(&self) -> String {
+            self.desktop_id.clone()
+        }
+
+        #[zbus(property)]
+        fn set_desktop_id(&mut self, id: String) {
+            self.desktop_id = id;
+        }
+
+        #[zbus(property)]
+        fn requested_accuracy_level(&self) -> u32 {
+            self.accuracy
+        }
+
+        #[zbus(property)]
+        fn set_requested_accuracy_level(&mut self, level: u32) {
+            self.accuracy = level;
+        }
+    }
+
+    struct MockLocation;
+
+    #[interface(name = "org.freedesktop.GeoClue2.Location")]
+    impl MockLocation {
+        #[zbus(property)]
+        fn latitude(&self) -> f64 {
+            69.6496
+        }
+
+        #[zbus(property)]
+        fn longitude(&self) -> f64 {
+            18.9560
+        }
+    }
+
+    /// Serve a GeoClue mock with a fix in Tromsø on the bus.
+    fn serve_mock(bus: &PrivateBus) -> Connection {
+        let client = MockClient {
+            started: Arc::new(Mutex::new(false)),
+            desktop_id: String::new(),
+            accuracy: 0,
+        };
+        bus.builder()
+            .name("org.freedesktop.GeoClue2")
+            .unwrap()
+            .serve_at("/org/freedesktop/GeoClue2/Manager", MockManager)
+            .unwrap()
+            .serve_at(CLIENT_PATH, client)
+            .unwrap()
+            .serve_at(LOCATION_PATH, MockLocation)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn locate_from_mock_service() {
+        let bus = PrivateBus::start();
+        let _service = serve_mock(&bus);
+
+        let conn = bus.connect();
+        let location = locate(&conn, Duration::from_secs(1)).unwrap();
+        assert_eq!(location, Some((69.6496, 18.9560)));
+    }
+
+    #[test]
+    fn caches_location_for_an_hour() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new("2024-06-01T12:20:00Z".parse().unwrap());
+        let http = FixtureTransport::default();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        let bus = PrivateBus::start();
+        let service = serve_mock(&bus);
+        assert_eq!(locate_and_cache(&ctx, &bus.connect(), Duration::from_secs(1)), Some((69.6496, 18.9560)));
+        drop(service);
+        drop(bus);
+
+        // Served from the cache without asking GeoClue again
+        clock.advance(chrono::Duration::minutes(30));
+        assert_eq!(get_location(&ctx), Some((69.6496, 18.9560)));
+    }
+}
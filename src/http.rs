@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+const HOMEPAGE: &str = "https://github.com/eegseth/waybar-weather-info";
+
+/// Status, headers and body of an HTTP response
+#[derive(Clone)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Response {
+    pub fn json(&self) -> Result<Value> {
+        serde_json::from_str(&self.body).map_err(|e| Error::Parse(e.to_string()))
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Delay requested by a `Retry-After` header, given either in seconds or as an HTTP date.
+    pub fn retry_after(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let value = self.header("retry-after")?.trim();
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Some(Duration::from_secs(seconds));
+        }
+        let date = DateTime::parse_from_rfc2822(value).ok()?;
+        (date.with_timezone(&Utc) - now).to_std().ok()
+    }
+}
+
+/// Outbound HTTP requests, so fetching can be tested against recorded responses.
+pub trait Transport {
+    fn get(&self, url: &str) -> Result<Response>;
+}
+
+/// User-Agent identifying this application, as required by MET Norway's terms of service.
+/// `contact` is an email address or URL where the user can be reached.
+pub fn user_agent(contact: Option<&str>) -> String {
+    match contact {
+        Some(contact) => format!("waybar-weather-info/{} (+{}; {})", env!("CARGO_PKG_VERSION"), HOMEPAGE, contact),
+        None => format!("waybar-weather-info/{} (+{})", env!("CARGO_PKG_VERSION"), HOMEPAGE),
+    }
+}
+
+/// Transport backed by a blocking reqwest client, shared by all outbound requests
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(contact: Option<&str>) -> Self {
+        let client = Client::builder()
+            .user_agent(user_agent(contact))
+            .build()
+            .expect("Failed to create HTTP client");
+        Self { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    /// Errors leave out the URL, as it can hold an API key
+    fn get(&self, url: &str) -> Result<Response> {
+        let response = self.client.get(url).send().map_err(|e| Error::Network(e.without_url().to_string()))?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str().to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = response.text().map_err(|e| Error::Network(e.without_url().to_string()))?;
+        Ok(Response { status, headers, body })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_agent_includes_version_and_contact() {
+        let version = env!("CARGO_PKG_VERSION");
+        assert_eq!(
+            user_agent(None),
+            format!("waybar-weather-info/{} (+https://github.com/eegseth/waybar-weather-info)", version)
+        );
+        assert_eq!(
+            user_agent(Some("ops@example.com")),
+            format!("waybar-weather-info/{} (+https://github.com/eegseth/waybar-weather-info; ops@example.com)", version)
+        );
+    }
+
+    #[test]
+    fn network_errors_leave_out_api_keys() {
+        // Nothing listens on port 1, so the connection is refused
+        let result = ReqwestTransport::new(None).get("http://127.0.0.1:1/forecast?appid=secret");
+        assert!(matches!(result, Err(Error::Network(message)) if !message.contains("secret")));
+    }
+}
+
+#[cfg(test)]
+pub mod mock {
+    use super::{Response, Transport};
+    use crate::error::{Error, Result};
+    use std::cell::RefCell;
+
+    /// Transport answering from recorded responses, matched by URL prefix.
+    #[derive(Default)]
+    pub struct FixtureTransport {
+        routes: Vec<(String, Response)>,
+        pub requests: RefCell<Vec<String>>,
+    }
+
+    impl FixtureTransport {
+        pub fn route(self, url_prefix: &str, status: u16, body: &str) -> Self {
+            self.route_with_headers(url_prefix, status, &[], body)
+        }
+
+        pub fn route_with_headers(mut self, url_prefix: &str, status: u16, headers: &[(&str, &str)], body: &str) -> Self {
+            let response = Response {
+                status,
+                headers: headers.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect(),
+                body: body.to_string(),
+            };
+            self.routes.push((url_prefix.to_string(), response));
+            self
+        }
+    }
+
+    impl Transport for FixtureTransport {
+        fn get(&self, url: &str) -> Result<Response> {
+            self.requests.borrow_mut().push(url.to_string());
+            self.routes
+                .iter()
+                .find(|(prefix, _)| url.starts_with(prefix.as_str()))
+                .map(|(_, response)| response.clone())
+                .ok_or_else(|| Error::Network(format!("no route for {}", url)))
+        }
+    }
+}
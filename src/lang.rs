@@ -1,5 +1,12 @@
 use clap::ValueEnum;
 
+use crate::airquality::AqiLevel;
+use crate::error::Error;
+use crate::pollen::{PollenLevel, PollenType};
+use crate::uv::UvCategory;
+use crate::wind::Beaufort;
+
+/// Language of tooltip labels and weather descriptions
 #[derive(Debug, Clone, ValueEnum)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum Lang {
@@ -64,6 +71,655 @@ impl Lang {
             Self::ES => "Precipitación",
         }
     }
+    pub fn altitude(&self) -> &str {
+        match self {
+            Self::EN => "Altitude",
+            Self::NB => "Høyde over havet",
+            Self::NN => "Høgd over havet",
+            Self::SME => "Allodat mearas",
+            Self::FR => "Altitude",
+            Self::DE => "Höhe",
+            Self::ES => "Altitud",
+        }
+    }
+    pub fn rain_starts_in(&self) -> &str {
+        match self {
+            Self::EN => "Rain in",
+            Self::NB => "Regn om",
+            Self::NN => "Regn om",
+            Self::SME => "Arvi boahtá",
+            Self::FR => "Pluie dans",
+            Self::DE => "Regen in",
+            Self::ES => "Lluvia en",
+        }
+    }
+    pub fn rain_stops_in(&self) -> &str {
+        match self {
+            Self::EN => "Rain stops in",
+            Self::NB => "Opphold om",
+            Self::NN => "Opphald om",
+            Self::SME => "Arvi nohká",
+            Self::FR => "Fin de la pluie dans",
+            Self::DE => "Regen endet in",
+            Self::ES => "Deja de llover en",
+        }
+    }
+    pub fn next_two_hours(&self) -> &str {
+        match self {
+            Self::EN => "Precipitation next 2 hours",
+            Self::NB => "Nedbør neste 2 timer",
+            Self::NN => "Nedbør neste 2 timar",
+            Self::SME => "Šaddadeapmi boahtte 2 diimmu",
+            Self::FR => "Précipitations des 2 prochaines heures",
+            Self::DE => "Niederschlag nächste 2 Stunden",
+            Self::ES => "Precipitación próximas 2 horas",
+        }
+    }
+    pub fn outdated(&self) -> &str {
+        match self {
+            Self::EN => "Outdated forecast",
+            Self::NB => "Utdatert varsel",
+            Self::NN => "Utdatert varsel",
+            Self::SME => "Boares dieđáhus",
+            Self::FR => "Prévisions obsolètes",
+            Self::DE => "Veraltete Vorhersage",
+            Self::ES => "Pronóstico desactualizado",
+        }
+    }
+    pub fn api_deprecated(&self) -> &str {
+        match self {
+            Self::EN => "The weather API version is deprecated, please update",
+            Self::NB => "Versjonen av vær-APIet er foreldet, oppdater programmet",
+            Self::NN => "Versjonen av vêr-APIet er forelda, oppdater programmet",
+            Self::SME => "Dálke-API veršuvdna lea boaresmuvvan, ođasmahte prográmma",
+            Self::FR => "La version de l'API météo est obsolète, veuillez mettre à jour",
+            Self::DE => "Die Version der Wetter-API ist veraltet, bitte aktualisieren",
+            Self::ES => "La versión de la API meteorológica está obsoleta, actualice",
+        }
+    }
+    pub fn weather_alerts(&self) -> &str {
+        match self {
+            Self::EN => "Weather warnings",
+            Self::NB => "Farevarsler",
+            Self::NN => "Farevarsel",
+            Self::SME => "Várrehusat",
+            Self::FR => "Alertes météo",
+            Self::DE => "Wetterwarnungen",
+            Self::ES => "Avisos meteorológicos",
+        }
+    }
+    pub fn rain_expected_at(&self) -> &str {
+        match self {
+            Self::EN => "Rain expected at",
+            Self::NB => "Regn ventet kl.",
+            Self::NN => "Regn venta kl.",
+            Self::SME => "Arvi vuordámuš dii.",
+            Self::FR => "Pluie attendue à",
+            Self::DE => "Regen erwartet um",
+            Self::ES => "Lluvia prevista a las",
+        }
+    }
+    pub fn temperature_below(&self) -> &str {
+        match self {
+            Self::EN => "Temperature below",
+            Self::NB => "Temperatur under",
+            Self::NN => "Temperatur under",
+            Self::SME => "Temperatuvra vuolábeallai",
+            Self::FR => "Température sous",
+            Self::DE => "Temperatur unter",
+            Self::ES => "Temperatura por debajo de",
+        }
+    }
+    pub fn temperature_above(&self) -> &str {
+        match self {
+            Self::EN => "Temperature above",
+            Self::NB => "Temperatur over",
+            Self::NN => "Temperatur over",
+            Self::SME => "Temperatuvra badjelii",
+            Self::FR => "Température au-dessus de",
+            Self::DE => "Temperatur über",
+            Self::ES => "Temperatura por encima de",
+        }
+    }
+    pub fn sunrise(&self) -> &str {
+        match self {
+            Self::EN => "Sunrise",
+            Self::NB => "Soloppgang",
+            Self::NN => "Soloppgang",
+            Self::SME => "Beaivvi badjáneapmi",
+            Self::FR => "Lever du soleil",
+            Self::DE => "Sonnenaufgang",
+            Self::ES => "Amanecer",
+        }
+    }
+    pub fn sunset(&self) -> &str {
+        match self {
+            Self::EN => "Sunset",
+            Self::NB => "Solnedgang",
+            Self::NN => "Solnedgang",
+            Self::SME => "Beaivvi luoitádeapmi",
+            Self::FR => "Coucher du soleil",
+            Self::DE => "Sonnenuntergang",
+            Self::ES => "Atardecer",
+        }
+    }
+    pub fn solar_noon(&self) -> &str {
+        match self {
+            Self::EN => "Solar noon",
+            Self::NB => "Sol i sør",
+            Self::NN => "Sol i sør",
+            Self::SME => "Beaivi lulde",
+            Self::FR => "Midi solaire",
+            Self::DE => "Sonnenhöchststand",
+            Self::ES => "Mediodía solar",
+        }
+    }
+    pub fn day_length(&self) -> &str {
+        match self {
+            Self::EN => "Daylight",
+            Self::NB => "Dagslys",
+            Self::NN => "Dagslys",
+            Self::SME => "Beaivečuovga",
+            Self::FR => "Durée du jour",
+            Self::DE => "Tageslänge",
+            Self::ES => "Duración del día",
+        }
+    }
+    pub fn midnight_sun(&self) -> &str {
+        match self {
+            Self::EN => "Midnight sun",
+            Self::NB => "Midnattssol",
+            Self::NN => "Midnattssol",
+            Self::SME => "Gaskaidjabeaivváš",
+            Self::FR => "Soleil de minuit",
+            Self::DE => "Mitternachtssonne",
+            Self::ES => "Sol de medianoche",
+        }
+    }
+    pub fn polar_night(&self) -> &str {
+        match self {
+            Self::EN => "Polar night",
+            Self::NB => "Mørketid",
+            Self::NN => "Mørketid",
+            Self::SME => "Skábma",
+            Self::FR => "Nuit polaire",
+            Self::DE => "Polarnacht",
+            Self::ES => "Noche polar",
+        }
+    }
+    pub fn moon(&self) -> &str {
+        match self {
+            Self::EN => "Moon",
+            Self::NB => "Månen",
+            Self::NN => "Månen",
+            Self::SME => "Mánnu",
+            Self::FR => "Lune",
+            Self::DE => "Mond",
+            Self::ES => "Luna",
+        }
+    }
+    pub fn uv_index(&self) -> &str {
+        match self {
+            Self::EN => "UV index",
+            Self::NB => "UV-indeks",
+            Self::NN => "UV-indeks",
+            Self::SME => "UV-indeksa",
+            Self::FR => "Indice UV",
+            Self::DE => "UV-Index",
+            Self::ES => "Índice UV",
+        }
+    }
+    pub fn max_today(&self) -> &str {
+        match self {
+            Self::EN => "max today",
+            Self::NB => "maks i dag",
+            Self::NN => "maks i dag",
+            Self::SME => "eanemus odne",
+            Self::FR => "max aujourd'hui",
+            Self::DE => "max. heute",
+            Self::ES => "máx. hoy",
+        }
+    }
+    pub fn air_quality(&self) -> &str {
+        match self {
+            Self::EN => "Air quality",
+            Self::NB => "Luftkvalitet",
+            Self::NN => "Luftkvalitet",
+            Self::SME => "Áibmokvalitehta",
+            Self::FR => "Qualité de l'air",
+            Self::DE => "Luftqualität",
+            Self::ES => "Calidad del aire",
+        }
+    }
+    pub fn pollen(&self) -> &str {
+        match self {
+            Self::EN => "Pollen",
+            Self::NB => "Pollen",
+            Self::NN => "Pollen",
+            Self::SME => "Pollen",
+            Self::FR => "Pollen",
+            Self::DE => "Pollen",
+            Self::ES => "Polen",
+        }
+    }
+    pub fn today(&self) -> &str {
+        match self {
+            Self::EN => "Today",
+            Self::NB => "I dag",
+            Self::NN => "I dag",
+            Self::SME => "Odne",
+            Self::FR => "Aujourd'hui",
+            Self::DE => "Heute",
+            Self::ES => "Hoy",
+        }
+    }
+    pub fn tomorrow(&self) -> &str {
+        match self {
+            Self::EN => "Tomorrow",
+            Self::NB => "I morgen",
+            Self::NN => "I morgon",
+            Self::SME => "Ihttin",
+            Self::FR => "Demain",
+            Self::DE => "Morgen",
+            Self::ES => "Mañana",
+        }
+    }
+    pub fn source(&self) -> &str {
+        match self {
+            Self::EN => "Source",
+            Self::NB => "Kilde",
+            Self::NN => "Kjelde",
+            Self::SME => "Gáldu",
+            Self::FR => "Source",
+            Self::DE => "Quelle",
+            Self::ES => "Fuente",
+        }
+    }
+    pub fn average(&self) -> &str {
+        match self {
+            Self::EN => "average",
+            Self::NB => "gjennomsnitt",
+            Self::NN => "gjennomsnitt",
+            Self::SME => "gaskamearálaš",
+            Self::FR => "moyenne",
+            Self::DE => "Mittelwert",
+            Self::ES => "promedio",
+        }
+    }
+    pub fn gusts(&self) -> &str {
+        match self {
+            Self::EN => "gusts",
+            Self::NB => "kast",
+            Self::NN => "kast",
+            Self::SME => "bossut",
+            Self::FR => "rafales",
+            Self::DE => "Böen",
+            Self::ES => "rachas",
+        }
+    }
+    /// Language prefix of the CAP `info` block to prefer; MetAlerts publishes Norwegian and English.
+    pub fn alert_language(&self) -> &str {
+        match self {
+            Self::NB | Self::NN | Self::SME => "no",
+            Self::EN | Self::FR | Self::DE | Self::ES => "en",
+        }
+    }
+
+    /// Sun protection advice for a UV category, following the WHO's recommendations.
+    pub fn uv_advice(&self, category: &UvCategory) -> &str {
+        match category {
+            UvCategory::Low => match self {
+                Self::EN => "No sun protection needed",
+                Self::NB => "Solbeskyttelse er ikke nødvendig",
+                Self::NN => "Solvern er ikkje naudsynt",
+                Self::SME => "Beaivesuodjalus ii dárbbašuvvo",
+                Self::FR => "Aucune protection solaire nécessaire",
+                Self::DE => "Kein Sonnenschutz nötig",
+                Self::ES => "No se necesita protección solar",
+            },
+            UvCategory::Moderate | UvCategory::High => match self {
+                Self::EN => "Seek shade at midday, wear a hat and sunscreen",
+                Self::NB => "Søk skygge midt på dagen, bruk hatt og solkrem",
+                Self::NN => "Søk skugge midt på dagen, bruk hatt og solkrem",
+                Self::SME => "Oza suoivana gaskabeaivve, geavat hattu ja beaivevoiddas",
+                Self::FR => "Cherchez l'ombre à midi, portez un chapeau et de la crème solaire",
+                Self::DE => "Mittags Schatten suchen, Hut und Sonnencreme tragen",
+                Self::ES => "Busque la sombra al mediodía, use sombrero y protector solar",
+            },
+            UvCategory::VeryHigh | UvCategory::Extreme => match self {
+                Self::EN => "Avoid being outside at midday, shade, hat and sunscreen are a must",
+                Self::NB => "Unngå å være ute midt på dagen, skygge, hatt og solkrem er et must",
+                Self::NN => "Unngå å vere ute midt på dagen, skugge, hatt og solkrem er eit must",
+                Self::SME => "Garvve olgun leahkima gaskabeaivve, suoivana, hattu ja beaivevoiddas leat dárbbašlaččat",
+                Self::FR => "Évitez de sortir à midi, ombre, chapeau et crème solaire indispensables",
+                Self::DE => "Mittags drinnen bleiben, Schatten, Hut und Sonnencreme sind ein Muss",
+                Self::ES => "Evite salir al mediodía, sombra, sombrero y protector solar imprescindibles",
+            },
+        }
+    }
+
+    pub fn aqi_level(&self, level: &AqiLevel) -> &str {
+        match level {
+            AqiLevel::Low => match self {
+                Self::EN => "Low",
+                Self::NB => "Lav",
+                Self::NN => "Låg",
+                Self::SME => "Vuollegis",
+                Self::FR => "Faible",
+                Self::DE => "Gering",
+                Self::ES => "Baja",
+            },
+            AqiLevel::Moderate => match self {
+                Self::EN => "Moderate",
+                Self::NB => "Moderat",
+                Self::NN => "Moderat",
+                Self::SME => "Gaskageardde",
+                Self::FR => "Modérée",
+                Self::DE => "Mäßig",
+                Self::ES => "Moderada",
+            },
+            AqiLevel::High => match self {
+                Self::EN => "High",
+                Self::NB => "Høy",
+                Self::NN => "Høg",
+                Self::SME => "Allat",
+                Self::FR => "Élevée",
+                Self::DE => "Hoch",
+                Self::ES => "Alta",
+            },
+            AqiLevel::VeryHigh => match self {
+                Self::EN => "Very high",
+                Self::NB => "Svært høy",
+                Self::NN => "Svært høg",
+                Self::SME => "Hui allat",
+                Self::FR => "Très élevée",
+                Self::DE => "Sehr hoch",
+                Self::ES => "Muy alta",
+            },
+        }
+    }
+
+    pub fn pollen_type(&self, pollen_type: &PollenType) -> &str {
+        match pollen_type {
+            PollenType::Birch => match self {
+                Self::EN => "Birch",
+                Self::NB => "Bjørk",
+                Self::NN => "Bjørk",
+                Self::SME => "Soahki",
+                Self::FR => "Bouleau",
+                Self::DE => "Birke",
+                Self::ES => "Abedul",
+            },
+            PollenType::Grass => match self {
+                Self::EN => "Grass",
+                Self::NB => "Gress",
+                Self::NN => "Gras",
+                Self::SME => "Rássi",
+                Self::FR => "Graminées",
+                Self::DE => "Gräser",
+                Self::ES => "Gramíneas",
+            },
+            PollenType::Mugwort => match self {
+                Self::EN => "Mugwort",
+                Self::NB => "Burot",
+                Self::NN => "Burot",
+                Self::SME => "Burot",
+                Self::FR => "Armoise",
+                Self::DE => "Beifuß",
+                Self::ES => "Artemisa",
+            },
+        }
+    }
+
+    pub fn pollen_level(&self, level: &PollenLevel) -> &str {
+        match level {
+            PollenLevel::None => match self {
+                Self::EN => "None",
+                Self::NB => "Ingen",
+                Self::NN => "Ingen",
+                Self::SME => "Ii makkárge",
+                Self::FR => "Nul",
+                Self::DE => "Keine",
+                Self::ES => "Nulo",
+            },
+            PollenLevel::Low => match self {
+                Self::EN => "Low",
+                Self::NB => "Lite",
+                Self::NN => "Lite",
+                Self::SME => "Unnán",
+                Self::FR => "Faible",
+                Self::DE => "Gering",
+                Self::ES => "Bajo",
+            },
+            PollenLevel::Moderate => match self {
+                Self::EN => "Moderate",
+                Self::NB => "Moderat",
+                Self::NN => "Moderat",
+                Self::SME => "Gaskageardde",
+                Self::FR => "Moyen",
+                Self::DE => "Mäßig",
+                Self::ES => "Moderado",
+            },
+            PollenLevel::High => match self {
+                Self::EN => "High",
+                Self::NB => "Kraftig",
+                Self::NN => "Kraftig",
+                Self::SME => "Garra",
+                Self::FR => "Élevé",
+                Self::DE => "Hoch",
+                Self::ES => "Alto",
+            },
+            PollenLevel::VeryHigh => match self {
+                Self::EN => "Very high",
+                Self::NB => "Ekstrem",
+                Self::NN => "Ekstrem",
+                Self::SME => "Hui garra",
+                Self::FR => "Très élevé",
+                Self::DE => "Sehr hoch",
+                Self::ES => "Muy alto",
+            },
+        }
+    }
+
+    /// Name of a Beaufort force, e.g. `Frisk bris`
+    pub fn beaufort(&self, force: &Beaufort) -> &str {
+        match force {
+            Beaufort::Calm => match self {
+                Self::EN => "Calm",
+                Self::NB => "Stille",
+                Self::NN => "Stille",
+                Self::SME => "Jaskat",
+                Self::FR => "Calme",
+                Self::DE => "Windstille",
+                Self::ES => "Calma",
+            },
+            Beaufort::LightAir => match self {
+                Self::EN => "Light air",
+                Self::NB => "Flau vind",
+                Self::NN => "Flau vind",
+                Self::SME => "Hui unna biegga",
+                Self::FR => "Très légère brise",
+                Self::DE => "Leiser Zug",
+                Self::ES => "Ventolina",
+            },
+            Beaufort::LightBreeze => match self {
+                Self::EN => "Light breeze",
+                Self::NB => "Svak vind",
+                Self::NN => "Svak vind",
+                Self::SME => "Unna biegga",
+                Self::FR => "Légère brise",
+                Self::DE => "Leichte Brise",
+                Self::ES => "Flojito",
+            },
+            Beaufort::GentleBreeze => match self {
+                Self::EN => "Gentle breeze",
+                Self::NB => "Lett bris",
+                Self::NN => "Lett bris",
+                Self::SME => "Geahppa biegga",
+                Self::FR => "Petite brise",
+                Self::DE => "Schwache Brise",
+                Self::ES => "Flojo",
+            },
+            Beaufort::ModerateBreeze => match self {
+                Self::EN => "Moderate breeze",
+                Self::NB => "Laber bris",
+                Self::NN => "Laber bris",
+                Self::SME => "Gaskageardde biegga",
+                Self::FR => "Jolie brise",
+                Self::DE => "Mäßige Brise",
+                Self::ES => "Bonancible",
+            },
+            Beaufort::FreshBreeze => match self {
+                Self::EN => "Fresh breeze",
+                Self::NB => "Frisk bris",
+                Self::NN => "Frisk bris",
+                Self::SME => "Garraset biegga",
+                Self::FR => "Bonne brise",
+                Self::DE => "Frische Brise",
+                Self::ES => "Fresquito",
+            },
+            Beaufort::StrongBreeze => match self {
+                Self::EN => "Strong breeze",
+                Self::NB => "Liten kuling",
+                Self::NN => "Liten kuling",
+                Self::SME => "Garra biegga",
+                Self::FR => "Vent frais",
+                Self::DE => "Starker Wind",
+                Self::ES => "Fresco",
+            },
+            Beaufort::NearGale => match self {
+                Self::EN => "Near gale",
+                Self::NB => "Stiv kuling",
+                Self::NN => "Stiv kuling",
+                Self::SME => "Hui garra biegga",
+                Self::FR => "Grand frais",
+                Self::DE => "Steifer Wind",
+                Self::ES => "Frescachón",
+            },
+            Beaufort::Gale => match self {
+                Self::EN => "Gale",
+                Self::NB => "Sterk kuling",
+                Self::NN => "Sterk kuling",
+                Self::SME => "Stuorra biegga",
+                Self::FR => "Coup de vent",
+                Self::DE => "Stürmischer Wind",
+                Self::ES => "Temporal",
+            },
+            Beaufort::StrongGale => match self {
+                Self::EN => "Strong gale",
+                Self::NB => "Liten storm",
+                Self::NN => "Liten storm",
+                Self::SME => "Hui stuorra biegga",
+                Self::FR => "Fort coup de vent",
+                Self::DE => "Sturm",
+                Self::ES => "Temporal fuerte",
+            },
+            Beaufort::Storm => match self {
+                Self::EN => "Storm",
+                Self::NB => "Full storm",
+                Self::NN => "Full storm",
+                Self::SME => "Bieggadálki",
+                Self::FR => "Tempête",
+                Self::DE => "Schwerer Sturm",
+                Self::ES => "Temporal duro",
+            },
+            Beaufort::ViolentStorm => match self {
+                Self::EN => "Violent storm",
+                Self::NB => "Sterk storm",
+                Self::NN => "Sterk storm",
+                Self::SME => "Garra bieggadálki",
+                Self::FR => "Violente tempête",
+                Self::DE => "Orkanartiger Sturm",
+                Self::ES => "Temporal muy duro",
+            },
+            Beaufort::Hurricane => match self {
+                Self::EN => "Hurricane",
+                Self::NB => "Orkan",
+                Self::NN => "Orkan",
+                Self::SME => "Orkána",
+                Self::FR => "Ouragan",
+                Self::DE => "Orkan",
+                Self::ES => "Temporal huracanado",
+            },
+        }
+    }
+
+    pub fn error_message(&self, error: &Error) -> &str {
+        match error {
+            Error::Network(_) => match self {
+                Self::EN => "Could not reach the weather service",
+                Self::NB => "Kunne ikke nå værtjenesten",
+                Self::NN => "Kunne ikkje nå vêrtenesta",
+                Self::SME => "Ii beassan dálkebálvalussii",
+                Self::FR => "Service météo injoignable",
+                Self::DE => "Wetterdienst nicht erreichbar",
+                Self::ES => "No se pudo contactar con el servicio meteorológico",
+            },
+            Error::HttpStatus(403) => match self {
+                Self::EN => "Access denied by the weather service",
+                Self::NB => "Tilgang nektet av værtjenesten",
+                Self::NN => "Tilgang nekta av vêrtenesta",
+                Self::SME => "Dálkebálvalus hilggui beassama",
+                Self::FR => "Accès refusé par le service météo",
+                Self::DE => "Zugriff vom Wetterdienst verweigert",
+                Self::ES => "Acceso denegado por el servicio meteorológico",
+            },
+            Error::Throttled(_) => match self {
+                Self::EN => "Too many requests to the weather service",
+                Self::NB => "For mange forespørsler til værtjenesten",
+                Self::NN => "For mange førespurnader til vêrtenesta",
+                Self::SME => "Menddo ollu jearaldagat dálkebálvalussii",
+                Self::FR => "Trop de requêtes au service météo",
+                Self::DE => "Zu viele Anfragen an den Wetterdienst",
+                Self::ES => "Demasiadas solicitudes al servicio meteorológico",
+            },
+            Error::HttpStatus(_) => match self {
+                Self::EN => "The weather service returned an error",
+                Self::NB => "Værtjenesten svarte med en feil",
+                Self::NN => "Vêrtenesta svara med ein feil",
+                Self::SME => "Dálkebálvalus vástidii meattáhusain",
+                Self::FR => "Le service météo a renvoyé une erreur",
+                Self::DE => "Der Wetterdienst meldete einen Fehler",
+                Self::ES => "El servicio meteorológico devolvió un error",
+            },
+            Error::Parse(_) => match self {
+                Self::EN => "Failed to parse weather data",
+                Self::NB => "Kunne ikke lese værdata",
+                Self::NN => "Kunne ikkje lese vêrdata",
+                Self::SME => "Ii sáhttán lohkat dálkedieđuid",
+                Self::FR => "Données météo illisibles",
+                Self::DE => "Wetterdaten konnten nicht gelesen werden",
+                Self::ES => "No se pudieron leer los datos meteorológicos",
+            },
+            Error::Location(_) => match self {
+                Self::EN => "Could not determine location",
+                Self::NB => "Kunne ikke bestemme posisjon",
+                Self::NN => "Kunne ikkje fastsetje posisjon",
+                Self::SME => "Ii gávnnahan sajádaga",
+                Self::FR => "Position introuvable",
+                Self::DE => "Standort konnte nicht bestimmt werden",
+                Self::ES => "No se pudo determinar la ubicación",
+            },
+            Error::CacheIo(_) => match self {
+                Self::EN => "Cache error",
+                Self::NB => "Feil i hurtigbufferen",
+                Self::NN => "Feil i snøggbufferen",
+                Self::SME => "Gaskavuorká meattáhus",
+                Self::FR => "Erreur de cache",
+                Self::DE => "Cache-Fehler",
+                Self::ES => "Error de caché",
+            },
+            Error::Config(_) => match self {
+                Self::EN => "Invalid configuration",
+                Self::NB => "Ugyldig konfigurasjon",
+                Self::NN => "Ugyldig konfigurasjon",
+                Self::SME => "Boasttu heivehus",
+                Self::FR => "Configuration invalide",
+                Self::DE => "Ungültige Konfiguration",
+                Self::ES => "Configuración no válida",
+            },
+        }
+    }
 
     pub fn weather_desc(&self, symbol: &str) -> &str {
         match (self, symbol) {
@@ -0,0 +1,58 @@
+//! Weather from [MET Norway](https://api.met.no/) and other providers for status bars.
+//!
+//! The `waybar-weather-info` binary is a thin wrapper around this library; the same
+//! fetching, caching and formatting can be used to build other status line modules:
+//!
+//! ```no_run
+//! use waybar_weather_info::cache::Cache;
+//! use waybar_weather_info::cli::{IndicatorStyle, TempFormat};
+//! use waybar_weather_info::clock::{Clock, SystemClock};
+//! use waybar_weather_info::http::ReqwestTransport;
+//! use waybar_weather_info::location::Coordinates;
+//! use waybar_weather_info::met::Met;
+//! use waybar_weather_info::{extract_current_weather, fetch_weather_data, format_indicator, Context};
+//!
+//! let http = ReqwestTransport::new(Some("me@example.com"));
+//! let cache = Cache::from_env();
+//! let ctx = Context { clock: &SystemClock, http: &http, cache: &cache };
+//!
+//! let forecast = fetch_weather_data(&ctx, &Met, &Coordinates::new(59.9139, 10.7522))?;
+//! let current = extract_current_weather(&forecast.timeseries, SystemClock.now(), false);
+//! println!("{}", format_indicator(&current, &IndicatorStyle::Concise, &TempFormat::Celsius));
+//! # Ok::<(), waybar_weather_info::Error>(())
+//! ```
+
+pub mod airquality;
+pub mod alerts;
+pub mod cache;
+pub mod chart;
+pub mod cli;
+pub mod clock;
+pub mod config;
+pub mod constants;
+pub mod dwd;
+pub mod error;
+pub mod forecast;
+pub mod format;
+pub mod geoclue;
+pub mod http;
+pub mod lang;
+pub mod location;
+pub mod met;
+pub mod moon;
+pub mod notify;
+pub mod nowcast;
+pub mod openmeteo;
+pub mod openweathermap;
+pub mod pollen;
+pub mod sun;
+pub mod uv;
+pub mod wind;
+
+#[cfg(test)]
+mod testbus;
+
+pub use error::{Error, Result};
+pub use forecast::{extract_current_weather, fetch_weather_data, Context, CurrentWeather, Forecast, ForecastEntry, WeatherProvider};
+pub use format::{build_tooltip, format_indicator};
+pub use lang::Lang;
@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::forecast::Context;
+
+/// Position to fetch the forecast for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub lat: f64,
+    pub lon: f64,
+    /// Whole meters above sea level. MET Norway uses its own terrain model when not given.
+    pub altitude: Option<i32>,
+}
+
+impl Coordinates {
+    pub fn new(lat: f64, lon: f64) -> Self {
+        Self { lat, lon, altitude: None }
+    }
+
+    /// Round to `precision` decimals, so nearby lookups share requests and cache files
+    /// and no more precise location than needed is sent to the API.
+    pub fn rounded(self, precision: u32) -> Self {
+        let factor = 10_f64.powi(precision.min(15) as i32);
+        Self {
+            lat: (self.lat * factor).round() / factor,
+            lon: (self.lon * factor).round() / factor,
+            altitude: self.altitude,
+        }
+    }
+}
+
+/// Resolve a `--location` argument to coordinates.
+pub fn parse_location(ctx: &Context, location: &str) -> Result<Coordinates> {
+    if location.contains(',') {
+        let parts: Vec<&str> = location.split(',').map(str::trim).collect();
+        if let [lat, lon, rest @ ..] = parts.as_slice() {
+            let altitude = match rest {
+                [] => Some(None),
+                [alt] => alt.parse::<f64>().ok().map(|alt| Some(alt.round() as i32)),
+                _ => None,
+            };
+            if let (Ok(lat), Ok(lon), Some(altitude)) = (lat.parse::<f64>(), lon.parse::<f64>(), altitude) {
+                return Ok(Coordinates { lat, lon, altitude });
+            }
+        }
+        return Err(Error::Location(format!("invalid coordinates '{}', expected 'lat,lon' or 'lat,lon,alt'", location)));
+    }
+    
+    // Location ID format not supported - use as fallback to IP geolocation
+    eprintln!("Warning: Location ID '{}' not directly supported. Using IP-based geolocation.", location);
+    let (lat, lon) = get_location_from_ip(ctx);
+    Ok(Coordinates::new(lat, lon))
+}
+
+/// Look up coordinates from the public IP address, cached for an hour.
+/// Falls back to Oslo, Norway if the lookup fails.
+pub fn get_location_from_ip(ctx: &Context) -> (f64, f64) {
+    const CACHE_FILE: &str = "location.json";
+    const CACHE_DURATION: Duration = Duration::from_secs(3600); // 1 hour
+    const DEFAULT_LAT: f64 = 59.911491; // Oslo, Norway
+    const DEFAULT_LON: f64 = 10.757933;
+    
+    // Try to use cached location
+    if let Some(cache_content) = ctx.cache.read(CACHE_FILE, CACHE_DURATION, ctx.clock) {
+        if let Ok(json) = serde_json::from_str::<Value>(&cache_content) {
+            let lat = json["latitude"].as_f64().unwrap_or(DEFAULT_LAT);
+            let lon = json["longitude"].as_f64().unwrap_or(DEFAULT_LON);
+            return (lat, lon);
+        }
+    }
+    
+    // Fetch fresh location from IP geolocation service
+    if let Ok(response) = ctx.http.get("https://ipapi.co/json/") {
+        if let Ok(json) = response.json() {
+            let lat = json["latitude"].as_f64().unwrap_or(DEFAULT_LAT);
+            let lon = json["longitude"].as_f64().unwrap_or(DEFAULT_LON);
+            
+            // Cache the location
+            let cache_data = serde_json::json!({
+                "latitude": lat,
+                "longitude": lon,
+            });
+            let _ = ctx.cache.write(CACHE_FILE, &cache_data.to_string(), ctx.clock);
+            
+            return (lat, lon);
+        }
+    }
+    
+    // Default to Oslo, Norway if geolocation fails
+    eprintln!("Warning: Could not determine location from IP. Using Oslo, Norway as default.");
+    (DEFAULT_LAT, DEFAULT_LON)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Cache;
+    use crate::clock::FixedClock;
+    use crate::http::mock::FixtureTransport;
+
+    const IPAPI_FIXTURE: &str = include_str!("../tests/fixtures/ipapi.json");
+    const IPAPI_URL: &str = "https://ipapi.co/json/";
+
+    #[test]
+    fn locates_from_ip_and_caches_location() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new("2024-06-01T12:20:00Z".parse().unwrap());
+        let http = FixtureTransport::default().route(IPAPI_URL, 200, IPAPI_FIXTURE);
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        assert_eq!(get_location_from_ip(&ctx), (59.9127, 10.7461));
+        clock.advance(chrono::Duration::minutes(30));
+        assert_eq!(get_location_from_ip(&ctx), (59.9127, 10.7461));
+        assert_eq!(http.requests.borrow().len(), 1);
+    }
+
+    #[test]
+    fn rejects_malformed_coordinates() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new("2024-06-01T12:20:00Z".parse().unwrap());
+        let http = FixtureTransport::default();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        assert_eq!(parse_location(&ctx, "59.9139,10.7522").unwrap(), Coordinates::new(59.9139, 10.7522));
+        assert_eq!(
+            parse_location(&ctx, "61.6368, 8.3124, 899.6").unwrap(),
+            Coordinates { lat: 61.6368, lon: 8.3124, altitude: Some(900) }
+        );
+        assert!(matches!(parse_location(&ctx, "59.9139,east"), Err(Error::Location(_))));
+        assert!(matches!(parse_location(&ctx, "59.9139,10.7522,high"), Err(Error::Location(_))));
+        assert!(matches!(parse_location(&ctx, "59.9139,10.7522,10,20"), Err(Error::Location(_))));
+        assert!(http.requests.borrow().is_empty());
+    }
+
+    #[test]
+    fn rounds_coordinates_to_precision() {
+        let oslo = Coordinates { lat: 59.911561, lon: 10.7492741, altitude: Some(23) };
+        assert_eq!(oslo.rounded(4), Coordinates { lat: 59.9116, lon: 10.7493, altitude: Some(23) });
+        assert_eq!(oslo.rounded(0), Coordinates { lat: 60.0, lon: 11.0, altitude: Some(23) });
+        assert_eq!(Coordinates::new(-33.86785, 151.20732).rounded(2), Coordinates::new(-33.87, 151.21));
+    }
+}
@@ -1,187 +1,252 @@
-use std::fs::{metadata, read_to_string, File};
-use std::io::Write;
+use std::io;
 use std::process::exit;
-use std::time::{Duration, SystemTime};
+use std::time::Duration;
 
+use chrono::Local;
 use clap::Parser;
-use reqwest::blocking::Client;
-use serde_json::Value;
 
-use crate::cli::Args;
-
-mod cli;
-mod constants;
-mod format;
-mod lang;
-
-pub struct CurrentWeather {
-    pub temperature: f64,
-    pub symbol_code: String,
-    pub wind_speed: f64,
-    pub humidity: f64,
-    pub precipitation: f64,
-}
+use waybar_weather_info::cache::Cache;
+use waybar_weather_info::cli::{Args, CacheAction, Command, Provider};
+use waybar_weather_info::clock::SystemClock;
+use waybar_weather_info::config::Config;
+use waybar_weather_info::dwd::Dwd;
+use waybar_weather_info::http::ReqwestTransport;
+use waybar_weather_info::location::Coordinates;
+use waybar_weather_info::met::Met;
+use waybar_weather_info::moon::MoonPhase;
+use waybar_weather_info::notify::TemperatureThresholds;
+use waybar_weather_info::openmeteo::OpenMeteo;
+use waybar_weather_info::openweathermap::OpenWeatherMap;
+use waybar_weather_info::pollen::{FileProvider, OpenMeteoProvider, PollenProvider};
+use waybar_weather_info::uv::UvCategory;
+use waybar_weather_info::{airquality, alerts, format, forecast, geoclue, location, notify, nowcast, sun, uv, Context, Error, Result, WeatherProvider};
 
 fn main() {
     let args = Args::parse();
 
-    let (lat, lon) = match &args.location {
-        Some(loc) => parse_location(loc),
-        None => get_location_from_ip(),
-    };
+    if let Err(e) = run(&args) {
+        eprintln!("Error: {}", e);
+        if args.command.is_none() {
+            print_output("❌", &format::build_error_tooltip(&e, &args.lang), &["error"]);
+        }
+        exit(e.exit_code());
+    }
+}
 
-    let weather_data = fetch_weather_data(lat, lon);
-    let current = extract_current_weather(&weather_data);
+fn run(args: &Args) -> Result<()> {
+    let config = Config::load(args.config.as_deref())?;
+    let contact = args.contact.as_deref().or(config.contact.as_deref());
     
-    let text = format::format_indicator(&current, &args.indicator_style, &args.temp_format);
-    let tooltip = format::build_tooltip(&weather_data, &args.lang, &args.tooltip_style, &args.temp_format);
-    
-    // Output JSON for Waybar
-    println!(
-        "{{\"text\":\"{}\",\"tooltip\":\"{}\",\"class\":\"{}\"}}",
-        text,
-        tooltip.replace('\n', "\\n").replace('"', "\\\""),
-        format::get_weather_class(&current.symbol_code)
-    );
-}
+    let http = ReqwestTransport::new(contact);
+    let cache = Cache::from_env();
+    let ctx = Context { clock: &SystemClock, http: &http, cache: &cache };
 
-fn parse_location(location: &str) -> (f64, f64) {
-    if location.contains(',') {
-        let parts: Vec<&str> = location.split(',').collect();
-        if parts.len() == 2 {
-            if let (Ok(lat), Ok(lon)) = (parts[0].parse::<f64>(), parts[1].parse::<f64>()) {
-                return (lat, lon);
+    match &args.command {
+        Some(Command::Cache { action }) => run_cache_command(&ctx, action),
+        None => {
+            let max_age = Duration::from_secs(config.cache_max_age_hours.saturating_mul(3600));
+            if let Err(e) = cache.evict(max_age, ctx.clock) {
+                eprintln!("Warning: Could not evict old cache files: {}", e);
             }
+            run_indicator(&ctx, args, &config)
         }
-        eprintln!("Error: Invalid coordinate format. Expected 'lat,lon'");
-        exit(1);
     }
-    
-    // Location ID format not supported - use as fallback to IP geolocation
-    eprintln!("Warning: Location ID '{}' not directly supported. Using IP-based geolocation.", location);
-    get_location_from_ip()
 }
 
-fn get_location_from_ip() -> (f64, f64) {
-    const CACHE_FILE: &str = "/tmp/waybar-weather-location.json";
-    const CACHE_DURATION_SECS: u64 = 3600; // 1 hour
-    const DEFAULT_LAT: f64 = 59.911491; // Oslo, Norway
-    const DEFAULT_LON: f64 = 10.757933;
+fn run_indicator(ctx: &Context, args: &Args, config: &Config) -> Result<()> {
+    let coords = match args.location.as_deref().or(config.location.as_deref()) {
+        Some(loc) => location::parse_location(ctx, loc)?,
+        None => {
+            let (lat, lon) = geoclue::get_location(ctx).unwrap_or_else(|| location::get_location_from_ip(ctx));
+            Coordinates::new(lat, lon)
+        }
+    };
+    let coords = coords.rounded(config.coordinate_precision);
+
+    let now = ctx.clock.now();
+    let providers = weather_providers(args, config)?;
+    let providers: Vec<&dyn WeatherProvider> = providers.iter().map(Box::as_ref).collect();
+    let mut forecast = if args.ensemble || config.ensemble {
+        forecast::fetch_ensemble(ctx, &providers, &coords)?
+    } else {
+        forecast::fetch_with_fallback(ctx, &providers, &coords)?
+    };
+    let today = now.with_timezone(&Local).date_naive();
+    // Before skipping past hours, so a peak earlier today still counts
+    let max_uv_today = uv::daily_max_uv_index(&forecast.timeseries, today, &Local);
+    forecast::skip_past_entries(&mut forecast.timeseries, now);
+    let weather_data = &forecast.timeseries;
+    let current = forecast::extract_current_weather(weather_data, now, args.interpolate);
     
-    // Try to use cached location
-    if let Ok(cache_content) = read_to_string(CACHE_FILE) {
-        if let Ok(metadata) = metadata(CACHE_FILE) {
-            if let Ok(modified) = metadata.modified() {
-                if let Ok(elapsed) = modified.elapsed() {
-                    if elapsed.as_secs() < CACHE_DURATION_SECS {
-                        if let Ok(json) = serde_json::from_str::<Value>(&cache_content) {
-                            let lat = json["latitude"].as_f64().unwrap_or(DEFAULT_LAT);
-                            let lon = json["longitude"].as_f64().unwrap_or(DEFAULT_LON);
-                            return (lat, lon);
-                        }
-                    }
-                }
-            }
+    let daylight = sun::daylight(today, coords.lat, coords.lon);
+    
+    let mut text = match args.format.as_deref().or(config.format.as_deref()) {
+        Some(template) => {
+            let mut values = format::indicator_placeholders(&current, &args.temp_format);
+            values.extend(format::sun_placeholders(&daylight));
+            format::render_template(template, &values)
         }
+        None => format::format_indicator(&current, &args.indicator_style, &args.temp_format),
+    };
+    let mut tooltip = format::build_tooltip(weather_data, &current, coords.altitude, &args.lang, &args.tooltip_style, &args.tooltip_layout, &args.temp_format);
+    tooltip.push_str(&format::build_sun_tooltip(&daylight, &args.lang));
+    tooltip.push_str(&format::build_moon_tooltip(&MoonPhase::at(now), &args.lang));
+    let mut classes = format::weather_classes(&current, config);
+    
+    if let Some(uv_index) = current.uv_index {
+        let max_today = max_uv_today.map_or(uv_index, |max| max.max(uv_index));
+        tooltip.push_str(&format::build_uv_tooltip(uv_index, max_today, &args.lang));
+        classes.push(UvCategory::from_index(uv_index).class());
     }
     
-    // Fetch fresh location from IP geolocation service
-    let client = Client::new();
-    if let Ok(response) = client.get("https://ipapi.co/json/").send() {
-        if let Ok(json) = response.json::<Value>() {
-            let lat = json["latitude"].as_f64().unwrap_or(DEFAULT_LAT);
-            let lon = json["longitude"].as_f64().unwrap_or(DEFAULT_LON);
-            
-            // Cache the location
-            let cache_data = serde_json::json!({
-                "latitude": lat,
-                "longitude": lon,
-            });
-            let _ = std::fs::write(CACHE_FILE, cache_data.to_string());
-            
-            return (lat, lon);
+    let nowcast = if args.nowcast || config.nowcast {
+        nowcast::fetch_nowcast(ctx, &coords)
+    } else {
+        None
+    };
+    if let Some(nowcast) = &nowcast {
+        if let Some(change) = nowcast.precipitation_change(now) {
+            text.push_str(&format!(" {}", format::format_precipitation_change(&change, &args.lang)));
         }
+        tooltip.push_str(&format::build_nowcast_tooltip(nowcast, now, &args.lang));
     }
     
-    // Default to Oslo, Norway if geolocation fails
-    eprintln!("Warning: Could not determine location from IP. Using Oslo, Norway as default.");
-    (DEFAULT_LAT, DEFAULT_LON)
-}
-
-fn fetch_weather_data(lat: f64, lon: f64) -> Value {
-    const CACHE_DURATION_SECS: u64 = 900; // 15 minutes
-    let cache_file = format!("/tmp/waybar-weather-{}-{}.json", lat, lon);
+    if args.air_quality || config.air_quality {
+        if let Some(air_quality) = airquality::fetch_air_quality(ctx, &coords) {
+            text.push_str(&format!(" {}", format::format_air_quality_badge(&air_quality)));
+            tooltip.push_str(&format::build_air_quality_tooltip(&air_quality, &args.lang));
+            classes.push(air_quality.level().class());
+        }
+    }
     
-    // Try to use cached data
-    if let Ok(json_str) = read_to_string(&cache_file) {
-        if let Ok(metadata) = metadata(&cache_file) {
-            if let Ok(modified) = metadata.modified() {
-                let cache_age = SystemTime::now()
-                    .duration_since(modified)
-                    .unwrap_or(Duration::from_secs(u64::MAX));
-                
-                if cache_age.as_secs() < CACHE_DURATION_SECS {
-                    if let Ok(json) = serde_json::from_str::<Value>(&json_str) {
-                        return json;
-                    }
+    if args.pollen || config.pollen {
+        let provider: Box<dyn PollenProvider> = match &config.pollen_file {
+            Some(path) => Box::new(FileProvider::new(path.clone())),
+            None => Box::new(OpenMeteoProvider),
+        };
+        match provider.fetch(ctx, &coords) {
+            Ok(days) => {
+                tooltip.push_str(&format::build_pollen_tooltip(&days, today, &args.lang));
+                let worst = days.iter().find(|day| day.date == today).and_then(|day| day.worst());
+                if let Some((pollen_type, level)) = worst.filter(|(_, level)| *level >= config.pollen_threshold) {
+                    text.push_str(&format!(" {}", format::format_pollen_badge(pollen_type, &args.lang)));
+                    classes.push("pollen");
+                    classes.push(level.class());
                 }
             }
+            Err(e) => eprintln!("Warning: Could not fetch pollen forecast: {}", e),
         }
     }
     
-    // Fetch from MET Norway API
-    let url = format!(
-        "https://api.met.no/weatherapi/locationforecast/2.0/compact?lat={}&lon={}",
-        lat, lon
-    );
-    
-    let client = Client::builder()
-        .user_agent("waybar-weather-info/0.1.0")
-        .build()
-        .expect("Failed to create HTTP client");
-    
-    let weather = match client.get(&url).send() {
-        Ok(response) => match response.json::<Value>() {
-            Ok(json) => json,
-            Err(e) => {
-                eprintln!("Error parsing weather data: {}", e);
-                println!("{{\"text\":\"❌\", \"tooltip\":\"Failed to parse weather data\"}}");
-                exit(1);
-            }
-        },
-        Err(e) => {
-            eprintln!("Error fetching weather data: {}", e);
-            println!("{{\"text\":\"❌\", \"tooltip\":\"Failed to fetch weather data\"}}");
-            exit(1);
+    // None if alerts are off or could not be fetched
+    let mut alerts = None;
+    if args.alerts || config.alerts {
+        match alerts::fetch_alerts(ctx, &coords, &args.lang) {
+            Ok(fetched) => alerts = Some(fetched),
+            Err(e) => eprintln!("Warning: Could not fetch weather alerts: {}", e),
         }
-    };
+    }
+    if let Some(alerts) = alerts.as_deref().filter(|alerts| !alerts.is_empty()) {
+        let most_severe = &alerts[0];
+        text.push_str(" ⚠️");
+        tooltip.push_str(&format::build_alerts_tooltip(alerts, &args.lang));
+        classes.push("alert");
+        classes.push(most_severe.severity.class());
+    }
     
-    // Cache the result
-    if let Ok(mut file) = File::create(&cache_file) {
-        if let Ok(json_str) = serde_json::to_string_pretty(&weather) {
-            let _ = file.write_all(json_str.as_bytes());
+    if args.notify || config.notify {
+        let thresholds = TemperatureThresholds {
+            below: config.notify_temperature_below,
+            above: config.notify_temperature_above,
+        };
+        let pending = notify::pending_notifications(
+            ctx,
+            weather_data,
+            nowcast.as_ref(),
+            alerts.as_deref(),
+            &thresholds,
+            &args.lang,
+            &args.temp_format,
+        );
+        match notify::send(&pending.notifications) {
+            Ok(()) => pending.mark_sent(ctx),
+            Err(e) => eprintln!("Warning: Could not send notifications: {}", e),
         }
     }
     
-    weather
+    tooltip.push_str(&format::build_source_note(&forecast.providers, &args.lang));
+    if let Some(age) = forecast.stale_age {
+        tooltip.push_str(&format::build_stale_note(age, &args.lang));
+        classes.push("stale");
+    }
+    if forecast.deprecated {
+        tooltip.push_str(&format!("\n<i>⚠️ {}</i>", args.lang.api_deprecated()));
+        classes.push("deprecated");
+    }
+    
+    print_output(&text, &tooltip, &classes);
+    Ok(())
 }
 
-fn extract_current_weather(data: &Value) -> CurrentWeather {
-    let current = &data["properties"]["timeseries"][0]["data"];
-    let instant = &current["instant"]["details"];
-    let next_1h = &current["next_1_hours"];
-    
-    CurrentWeather {
-        temperature: instant["air_temperature"].as_f64().unwrap_or(0.0),
-        symbol_code: next_1h["summary"]["symbol_code"]
-            .as_str()
-            .unwrap_or("cloudy")
-            .to_string(),
-        wind_speed: instant["wind_speed"].as_f64().unwrap_or(0.0),
-        humidity: instant["relative_humidity"].as_f64().unwrap_or(0.0),
-        precipitation: next_1h["details"]["precipitation_amount"]
-            .as_f64()
-            .unwrap_or(0.0),
+/// Weather providers from the command line or config file, MET Norway by default
+fn weather_providers(args: &Args, config: &Config) -> Result<Vec<Box<dyn WeatherProvider>>> {
+    let selected = if !args.provider.is_empty() {
+        args.provider.as_slice()
+    } else if !config.providers.is_empty() {
+        config.providers.as_slice()
+    } else {
+        &[Provider::Met]
+    };
+    selected.iter().map(|provider| weather_provider(provider, config)).collect()
+}
+
+fn weather_provider(provider: &Provider, config: &Config) -> Result<Box<dyn WeatherProvider>> {
+    match provider {
+        Provider::Met => Ok(Box::new(Met)),
+        Provider::OpenMeteo => Ok(Box::new(OpenMeteo)),
+        Provider::OpenWeatherMap => match &config.openweathermap_api_key {
+            Some(api_key) => Ok(Box::new(OpenWeatherMap::new(api_key.clone()))),
+            None => Err(Error::Config("the openweathermap provider needs openweathermap_api_key in the config file".to_string())),
+        },
+        Provider::Dwd => Ok(Box::new(Dwd)),
     }
 }
 
+/// Print JSON for Waybar
+fn print_output(text: &str, tooltip: &str, classes: &[&str]) {
+    println!(
+        "{}",
+        serde_json::json!({
+            "text": text,
+            "tooltip": tooltip,
+            "class": classes,
+        })
+    );
+}
+
+fn run_cache_command(ctx: &Context, action: &CacheAction) -> Result<()> {
+    match action {
+        CacheAction::Clear => {
+            let count = ctx.cache.clear(ctx.clock)?;
+            println!("Removed {} cached file(s)", count);
+        }
+        CacheAction::Info => {
+            let Some(dir) = ctx.cache.dir() else {
+                return Err(Error::CacheIo(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "neither XDG_CACHE_HOME nor HOME is set",
+                )));
+            };
+            println!("Cache directory: {}", dir.display());
+            
+            let entries = ctx.cache.entries(ctx.clock)?;
+            if entries.is_empty() {
+                println!("Cache is empty");
+            }
+            for entry in entries {
+                println!("{:<48} {:>8} bytes  {:>6} min old", entry.name, entry.size, entry.age.as_secs() / 60);
+            }
+        }
+    }
+    Ok(())
+}
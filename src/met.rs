@@ -0,0 +1,99 @@
+// Locationforecast from MET Norway, covering the whole world with the best detail in the Nordic countries
+// https://api.met.no/weatherapi/locationforecast/2.0/documentation
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::forecast::{ForecastEntry, Period, WeatherProvider};
+use crate::location::Coordinates;
+
+/// MET Norway's `complete` locationforecast
+pub struct Met;
+
+impl WeatherProvider for Met {
+    fn name(&self) -> &'static str {
+        "met"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "MET Norway"
+    }
+
+    fn url(&self, coords: &Coordinates, _now: DateTime<Utc>) -> String {
+        match coords.altitude {
+            Some(altitude) => format!(
+                "https://api.met.no/weatherapi/locationforecast/2.0/complete?lat={}&lon={}&altitude={}",
+                coords.lat, coords.lon, altitude
+            ),
+            None => format!(
+                "https://api.met.no/weatherapi/locationforecast/2.0/complete?lat={}&lon={}",
+                coords.lat, coords.lon
+            ),
+        }
+    }
+
+    fn parse(&self, data: &Value) -> Result<Vec<ForecastEntry>> {
+        let timeseries = data["properties"]["timeseries"]
+            .as_array()
+            .ok_or_else(|| Error::Parse("missing properties.timeseries".to_string()))?;
+
+        Ok(timeseries.iter().filter_map(parse_entry).collect())
+    }
+}
+
+fn parse_entry(entry: &Value) -> Option<ForecastEntry> {
+    let time = entry["time"].as_str()?.parse::<DateTime<Utc>>().ok()?;
+    let details = &entry["data"]["instant"]["details"];
+    Some(ForecastEntry {
+        time,
+        temperature: details["air_temperature"].as_f64(),
+        wind_speed: details["wind_speed"].as_f64(),
+        wind_gust: details["wind_speed_of_gust"].as_f64(),
+        humidity: details["relative_humidity"].as_f64(),
+        uv_index: details["ultraviolet_index_clear_sky"].as_f64(),
+        temperature_range: None,
+        next_hour: parse_period(&entry["data"]["next_1_hours"]),
+        next_6_hours: parse_period(&entry["data"]["next_6_hours"]),
+    })
+}
+
+fn parse_period(period: &Value) -> Option<Period> {
+    if period.is_null() {
+        return None;
+    }
+    let details = &period["details"];
+    Some(Period {
+        symbol_code: period["summary"]["symbol_code"].as_str().map(str::to_string),
+        precipitation: details["precipitation_amount"].as_f64(),
+        precipitation_range: details["precipitation_amount_min"]
+            .as_f64()
+            .zip(details["precipitation_amount_max"].as_f64()),
+        probability: details["probability_of_precipitation"].as_f64(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MET_FIXTURE: &str = include_str!("../tests/fixtures/met_locationforecast_complete.json");
+
+    #[test]
+    fn normalizes_timeseries() {
+        let data = serde_json::from_str(MET_FIXTURE).unwrap();
+        let timeseries = Met.parse(&data).unwrap();
+        let first = &timeseries[0];
+        assert_eq!(first.time, "2024-06-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert_eq!(first.uv_index, Some(7.1));
+        assert!(first.next_hour.as_ref().is_some_and(|period| period.symbol_code.is_some()));
+
+        let rainy = timeseries[13].next_hour.as_ref().unwrap();
+        assert_eq!(rainy.precipitation, Some(0.5));
+        assert_eq!(rainy.precipitation_range, Some((0.2, 1.5)));
+        assert_eq!(rainy.probability, Some(70.0));
+
+        // Far ahead, MET only forecasts 6-hour periods
+        assert!(timeseries.last().unwrap().next_hour.is_none());
+        assert!(Met.parse(&serde_json::json!({})).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+// Moon phase from the mean synodic month, accurate to within a day
+use std::f64::consts::PI;
+
+use chrono::{DateTime, Utc};
+
+/// Mean time between two new moons, in days
+const SYNODIC_MONTH_DAYS: f64 = 29.530588853;
+const PHASE_ICONS: [&str; 8] = ["🌑", "🌒", "🌓", "🌔", "🌕", "🌖", "🌗", "🌘"];
+
+/// Phase of the moon at a point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoonPhase {
+    /// Fraction of the lunar cycle since the last new moon, from 0 up to 1
+    pub cycle: f64,
+}
+
+impl MoonPhase {
+    pub fn at(time: DateTime<Utc>) -> Self {
+        // A new moon on 2000-01-06 18:14 UTC
+        let reference = DateTime::from_timestamp(947_182_440, 0).unwrap();
+        let days = (time - reference).num_seconds() as f64 / 86_400.0;
+        Self { cycle: (days / SYNODIC_MONTH_DAYS).rem_euclid(1.0) }
+    }
+
+    /// Illuminated fraction of the moon's disc, from 0 to 1
+    pub fn illumination(&self) -> f64 {
+        (1.0 - (2.0 * PI * self.cycle).cos()) / 2.0
+    }
+
+    /// Emoji of the nearest of the eight principal phases, as seen from the northern hemisphere
+    pub fn icon(&self) -> &'static str {
+        PHASE_ICONS[(self.cycle * PHASE_ICONS.len() as f64).round() as usize % PHASE_ICONS.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phase(time: &str) -> MoonPhase {
+        MoonPhase::at(time.parse().unwrap())
+    }
+
+    #[test]
+    fn finds_principal_phases() {
+        let new = phase("2024-06-06T12:38:00Z");
+        assert_eq!(new.icon(), "🌑");
+        assert!(new.illumination() < 0.03);
+
+        let first_quarter = phase("2024-06-14T05:18:00Z");
+        assert_eq!(first_quarter.icon(), "🌓");
+        assert!((first_quarter.illumination() - 0.5).abs() < 0.1);
+
+        let full = phase("2024-06-22T01:08:00Z");
+        assert_eq!(full.icon(), "🌕");
+        assert!(full.illumination() > 0.97);
+
+        assert_eq!(phase("2024-06-26T12:00:00Z").icon(), "🌖");
+    }
+}
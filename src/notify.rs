@@ -0,0 +1,416 @@
+// Desktop notifications through org.freedesktop.Notifications on the session D-Bus
+// https://specifications.freedesktop.org/notification-spec/latest/
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zbus::blocking::Connection;
+use zbus::proxy;
+
+use crate::alerts::{Alert, Severity};
+use crate::cli::TempFormat;
+use crate::forecast::{Context, ForecastEntry};
+use crate::format;
+use crate::lang::Lang;
+use crate::nowcast::{Nowcast, PrecipitationChange, RAIN_THRESHOLD};
+
+const APP_NAME: &str = "waybar-weather-info";
+const STATE_FILE: &str = "notifications.json";
+/// How far ahead rain is announced when there is no nowcast
+const RAIN_HORIZON_HOURS: i64 = 2;
+/// How far ahead temperature thresholds are checked
+const TEMPERATURE_HORIZON_HOURS: i64 = 12;
+
+const URGENCY_NORMAL: u8 = 1;
+const URGENCY_CRITICAL: u8 = 2;
+
+#[proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, zbus::zvariant::Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+    pub critical: bool,
+}
+
+/// Temperatures to notify about, in the unit of the selected temperature format
+#[derive(Debug, Default)]
+pub struct TemperatureThresholds {
+    pub below: Option<f64>,
+    pub above: Option<f64>,
+}
+
+/// Notifications to send, with the state to save once they have been sent
+pub struct PendingNotifications {
+    pub notifications: Vec<Notification>,
+    state: NotifiedState,
+}
+
+impl PendingNotifications {
+    /// Remember the notifications as sent, so they are not repeated on the next run.
+    /// Only call this after sending succeeded, or they are never delivered.
+    pub fn mark_sent(&self, ctx: &Context) {
+        if let Ok(json_str) = serde_json::to_string(&self.state) {
+            if let Err(e) = ctx.cache.write(STATE_FILE, &json_str, ctx.clock) {
+                eprintln!("Warning: Could not save notification state: {}", e);
+            }
+        }
+    }
+}
+
+/// What has been notified already, so the same event is not announced on every run.
+/// Flags are cleared once their condition no longer holds.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct NotifiedState {
+    rain: bool,
+    temperature_below: bool,
+    temperature_above: bool,
+    alerts: Vec<String>,
+}
+
+/// Notifications for events not announced before: rain starting soon, the temperature
+/// crossing a threshold within 12 hours and new weather warnings.
+/// `timeseries` must start at the current hour, see [`crate::forecast::skip_past_entries`].
+/// `alerts` is `None` when they could not be fetched, which keeps the known warnings.
+pub fn pending_notifications(
+    ctx: &Context,
+    timeseries: &[ForecastEntry],
+    nowcast: Option<&Nowcast>,
+    alerts: Option<&[Alert]>,
+    thresholds: &TemperatureThresholds,
+    lang: &Lang,
+    temp_format: &TempFormat,
+) -> PendingNotifications {
+    let now = ctx.clock.now();
+    let mut state: NotifiedState = ctx
+        .cache
+        .read_with_age(STATE_FILE, ctx.clock)
+        .and_then(|(json_str, _)| serde_json::from_str(&json_str).ok())
+        .unwrap_or_default();
+    let mut notifications = Vec::new();
+
+    let rain = upcoming_rain(timeseries, now, nowcast, lang);
+    if let Some(summary) = &rain {
+        if !state.rain {
+            notifications.push(Notification { summary: summary.clone(), body: String::new(), critical: false });
+        }
+    }
+    state.rain = rain.is_some();
+
+    let below = thresholds.below.and_then(|threshold| {
+        let threshold_c = to_celsius(threshold, temp_format);
+        temperature_crossing(timeseries, now, |temp| temp < threshold_c).map(|crossing| (threshold_c, crossing))
+    });
+    if let (Some((threshold_c, (time, temp))), false) = (below, state.temperature_below) {
+        notifications.push(temperature_notification(lang.temperature_below(), threshold_c, time, temp, temp_format));
+    }
+    state.temperature_below = below.is_some();
+
+    let above = thresholds.above.and_then(|threshold| {
+        let threshold_c = to_celsius(threshold, temp_format);
+        temperature_crossing(timeseries, now, |temp| temp > threshold_c).map(|crossing| (threshold_c, crossing))
+    });
+    if let (Some((threshold_c, (time, temp))), false) = (above, state.temperature_above) {
+        notifications.push(temperature_notification(lang.temperature_above(), threshold_c, time, temp, temp_format));
+    }
+    state.temperature_above = above.is_some();
+
+    if let Some(alerts) = alerts {
+        for alert in alerts.iter().filter(|alert| !state.alerts.contains(&alert.identifier)) {
+            // Daemons like mako and dunst render the summary as markup too
+            let mut body = format::format_alert_validity(alert);
+            if let Some(instruction) = &alert.instruction {
+                body.push_str(&format!("\n{}", format::escape_markup(instruction)));
+            }
+            notifications.push(Notification {
+                summary: format!("⚠️ {}", format::escape_markup(&alert.headline)),
+                body,
+                critical: alert.severity == Severity::Red,
+            });
+        }
+        state.alerts = alerts.iter().map(|alert| alert.identifier.clone()).collect();
+    }
+
+    PendingNotifications { notifications, state }
+}
+
+/// Send notifications to the notification daemon on the session bus.
+pub fn send(notifications: &[Notification]) -> zbus::Result<()> {
+    if notifications.is_empty() {
+        return Ok(());
+    }
+    send_with(&Connection::session()?, notifications)
+}
+
+fn send_with(conn: &Connection, notifications: &[Notification]) -> zbus::Result<()> {
+    let proxy = NotificationsProxyBlocking::new(conn)?;
+    for notification in notifications {
+        let urgency = if notification.critical { URGENCY_CRITICAL } else { URGENCY_NORMAL };
+        let hints = HashMap::from([("urgency", urgency.into())]);
+        // -1 lets the notification daemon choose how long to show it
+        proxy.notify(APP_NAME, 0, "", &notification.summary, &notification.body, &[], hints, -1)?;
+    }
+    Ok(())
+}
+
+/// Summary announcing rain, if it is dry now and rain is expected soon.
+fn upcoming_rain(timeseries: &[ForecastEntry], now: DateTime<Utc>, nowcast: Option<&Nowcast>, lang: &Lang) -> Option<String> {
+    // The radar nowcast is more precise than the hourly forecast where it is available
+    if let Some(nowcast) = nowcast {
+        return match nowcast.precipitation_change(now)? {
+            change @ PrecipitationChange::StartsIn(_) => Some(format::format_precipitation_change(&change, lang)),
+            PrecipitationChange::StopsIn(_) => None,
+        };
+    }
+
+    let precipitation = |entry: &ForecastEntry| {
+        entry.next_hour.as_ref().and_then(|period| period.precipitation).unwrap_or(0.0)
+    };
+    if precipitation(timeseries.first()?) >= RAIN_THRESHOLD {
+        return None;
+    }
+
+    let entry = forecast_entries(timeseries, now, RAIN_HORIZON_HOURS)
+        .skip(1)
+        .find(|entry| precipitation(entry) >= RAIN_THRESHOLD)?;
+    Some(format!("☔ {} {}", lang.rain_expected_at(), entry.time.with_timezone(&chrono::Local).format("%H:%M")))
+}
+
+/// Time and temperature of the first entry within 12 hours where `beyond` holds,
+/// provided it does not hold for the current hour.
+fn temperature_crossing(timeseries: &[ForecastEntry], now: DateTime<Utc>, beyond: impl Fn(f64) -> bool) -> Option<(DateTime<Utc>, f64)> {
+    if beyond(timeseries.first()?.temperature?) {
+        return None;
+    }
+
+    forecast_entries(timeseries, now, TEMPERATURE_HORIZON_HOURS)
+        .filter_map(|entry| Some((entry.time, entry.temperature?)))
+        .find(|(_, temp)| beyond(*temp))
+}
+
+fn forecast_entries(timeseries: &[ForecastEntry], now: DateTime<Utc>, horizon_hours: i64) -> impl Iterator<Item = &ForecastEntry> {
+    let end = now + chrono::Duration::hours(horizon_hours);
+    timeseries.iter().take_while(move |entry| entry.time <= end)
+}
+
+fn temperature_notification(label: &str, threshold_c: f64, time: DateTime<Utc>, temp_c: f64, temp_format: &TempFormat) -> Notification {
+    Notification {
+        summary: format!("🌡️ {} {}", label, format::format_temp(threshold_c, temp_format)),
+        body: format!("{}: {}", time.with_timezone(&chrono::Local).format("%H:%M"), format::format_temp(temp_c, temp_format)),
+        critical: false,
+    }
+}
+
+fn to_celsius(temp: f64, temp_format: &TempFormat) -> f64 {
+    match temp_format {
+        TempFormat::Celsius => temp,
+        TempFormat::Fahrenheit => (temp - 32.0) * 5.0 / 9.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Cache;
+    use crate::clock::{Clock, FixedClock};
+    use crate::http::mock::FixtureTransport;
+    use crate::forecast::{skip_past_entries, WeatherProvider};
+    use crate::met::Met;
+    use std::sync::{Arc, Mutex};
+    use crate::testbus::PrivateBus;
+    use zbus::interface;
+
+    const MET_FIXTURE: &str = include_str!("../tests/fixtures/met_locationforecast_complete.json");
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    fn forecast_at(now: DateTime<Utc>) -> Vec<ForecastEntry> {
+        let mut timeseries = Met.parse(&serde_json::from_str(MET_FIXTURE).unwrap()).unwrap();
+        skip_past_entries(&mut timeseries, now);
+        timeseries
+    }
+
+    fn summaries(notifications: &[Notification]) -> Vec<&str> {
+        notifications.iter().map(|notification| notification.summary.as_str()).collect()
+    }
+
+    /// Pending notifications, marked as sent as after a successful `send`
+    fn sent(ctx: &Context, timeseries: &[ForecastEntry], alerts: Option<&[Alert]>, thresholds: &TemperatureThresholds, temp_format: &TempFormat) -> Vec<Notification> {
+        let pending = pending_notifications(ctx, timeseries, None, alerts, thresholds, &Lang::EN, temp_format);
+        pending.mark_sent(ctx);
+        pending.notifications
+    }
+
+    #[test]
+    fn announces_rain_from_forecast_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T21:20:00Z"));
+        let http = FixtureTransport::default();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+        let thresholds = TemperatureThresholds::default();
+        let notify = |ctx: &Context| sent(ctx, &forecast_at(ctx.clock.now()), None, &thresholds, &TempFormat::Celsius);
+
+        // Rain starts at midnight, more than 2 hours ahead
+        assert!(notify(&ctx).is_empty());
+
+        clock.advance(chrono::Duration::hours(1));
+        let notifications = notify(&ctx);
+        assert_eq!(notifications.len(), 1);
+        assert!(notifications[0].summary.starts_with("☔ Rain expected at "));
+
+        clock.advance(chrono::Duration::minutes(30));
+        assert!(notify(&ctx).is_empty());
+    }
+
+    #[test]
+    fn announces_temperature_crossing_threshold_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T14:20:00Z"));
+        let http = FixtureTransport::default();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+        let thresholds = TemperatureThresholds { below: Some(59.0), above: Some(70.0) };
+
+        let data = forecast_at(clock.now());
+        let notifications = sent(&ctx, &data, None, &thresholds, &TempFormat::Fahrenheit);
+        assert_eq!(summaries(&notifications), ["🌡️ Temperature below 59°F"]);
+        assert!(notifications[0].body.ends_with(": 58°F"));
+
+        let notifications = sent(&ctx, &data, None, &thresholds, &TempFormat::Fahrenheit);
+        assert!(notifications.is_empty());
+    }
+
+    #[test]
+    fn announces_new_alerts_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:00:00Z"));
+        let http = FixtureTransport::default();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+        let thresholds = TemperatureThresholds::default();
+        let data = forecast_at(clock.now());
+        let alert = || Alert {
+            identifier: "2.49.0.1.578.0.20240601090000.001".to_string(),
+            severity: Severity::Yellow,
+            headline: "Heavy rain <yellow level>".to_string(),
+            description: "Locally up to 40 mm of rain in 6 hours.".to_string(),
+            instruction: Some("Keep drains & culverts clear.".to_string()),
+            onset: Some(at("2024-06-01T14:00:00Z")),
+            expires: at("2024-06-01T22:00:00Z"),
+            areas: Vec::new(),
+        };
+
+        // Not marked as sent, e.g. because there was no notification daemon
+        let pending = pending_notifications(&ctx, &data, None, Some(&[alert()]), &thresholds, &Lang::EN, &TempFormat::Celsius);
+        assert_eq!(pending.notifications.len(), 1);
+
+        let notifications = sent(&ctx, &data, Some(&[alert()]), &thresholds, &TempFormat::Celsius);
+        assert_eq!(summaries(&notifications), ["⚠️ Heavy rain &lt;yellow level&gt;"]);
+        assert!(notifications[0].body.ends_with("\nKeep drains &amp; culverts clear."));
+        assert!(!notifications[0].critical);
+
+        let notifications = sent(&ctx, &data, Some(&[alert()]), &thresholds, &TempFormat::Celsius);
+        assert!(notifications.is_empty());
+    }
+
+    #[test]
+    fn keeps_known_alerts_when_fetching_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:00:00Z"));
+        let http = FixtureTransport::default();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+        let thresholds = TemperatureThresholds::default();
+        let data = forecast_at(clock.now());
+        let alerts = [Alert {
+            identifier: "2.49.0.1.578.0.20240601090000.002".to_string(),
+            severity: Severity::Red,
+            headline: "Storm, red level".to_string(),
+            description: "Hurricane-force gusts along the coast.".to_string(),
+            instruction: None,
+            onset: None,
+            expires: at("2024-06-02T06:00:00Z"),
+            areas: Vec::new(),
+        }];
+
+        assert_eq!(sent(&ctx, &data, Some(&alerts), &thresholds, &TempFormat::Celsius).len(), 1);
+        assert!(sent(&ctx, &data, None, &thresholds, &TempFormat::Celsius).is_empty());
+        assert!(sent(&ctx, &data, Some(&alerts), &thresholds, &TempFormat::Celsius).is_empty());
+    }
+
+    struct MockNotifications {
+        received: Arc<Mutex<Vec<(String, String, u8)>>>,
+    }
+
+    #[interface(name = "org.freedesktop.Notifications")]
+    impl MockNotifications {
+        #[allow(clippy::too_many_arguments)]
+        fn notify(
+            &self,
+            _app_name: String,
+            _replaces_id: u32,
+            _app_icon: String,
+            summary: String,
+            body: String,
+            _actions: Vec<String>,
+            hints: HashMap<String, zbus::zvariant::OwnedValue>,
+            _expire_timeout: i32,
+        ) -> u32 {
+            let urgency = hints.get("urgency").and_then(|value| u8::try_from(value).ok()).unwrap_or(0);
+            let mut received = self.received.lock().unwrap();
+            received.push((summary, body, urgency));
+            received.len() as u32
+        }
+    }
+
+    #[test]
+    fn sends_to_mock_notification_daemon() {
+        let bus = PrivateBus::start();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let _service = bus
+            .builder()
+            .name("org.freedesktop.Notifications")
+            .unwrap()
+            .serve_at("/org/freedesktop/Notifications", MockNotifications { received: received.clone() })
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let conn = bus.connect();
+        let notifications = [
+            Notification { summary: "☔ Rain in 10 min".to_string(), body: String::new(), critical: false },
+            Notification { summary: "⚠️ Storm".to_string(), body: "Stay indoors.".to_string(), critical: true },
+        ];
+        send_with(&conn, &notifications).unwrap();
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            [
+                ("☔ Rain in 10 min".to_string(), String::new(), URGENCY_NORMAL),
+                ("⚠️ Storm".to_string(), "Stay indoors.".to_string(), URGENCY_CRITICAL),
+            ]
+        );
+    }
+}
@@ -0,0 +1,155 @@
+// Radar-based precipitation nowcast from MET Norway, available for the Nordic countries
+// https://api.met.no/weatherapi/nowcast/2.0/documentation
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::forecast::{fetch_json, Context};
+use crate::location::Coordinates;
+
+/// Precipitation rates below this many mm/h count as dry
+pub const RAIN_THRESHOLD: f64 = 0.1;
+
+const BACKOFF_FILE: &str = "backoff-nowcast.json";
+
+/// Precipitation rate in mm/h at 5 minute intervals for the next two hours
+pub struct Nowcast {
+    pub rates: Vec<(DateTime<Utc>, f64)>,
+}
+
+/// When the precipitation is expected to change
+#[derive(Debug, PartialEq)]
+pub enum PrecipitationChange {
+    /// Dry now, precipitation starts in the given number of minutes
+    StartsIn(i64),
+    /// Precipitation now, stops in the given number of minutes
+    StopsIn(i64),
+}
+
+/// Fetch the nowcast, cached for 5 minutes.
+/// Returns `None` outside radar coverage or if the nowcast is unavailable.
+pub fn fetch_nowcast(ctx: &Context, coords: &Coordinates) -> Option<Nowcast> {
+    const CACHE_DURATION: Duration = Duration::from_secs(300); // 5 minutes
+    let cache_file = format!("nowcast-{}-{}.json", coords.lat, coords.lon);
+
+    let url = format!(
+        "https://api.met.no/weatherapi/nowcast/2.0/complete?lat={}&lon={}",
+        coords.lat, coords.lon
+    );
+
+    // Locations outside the Nordic countries are answered with 422
+    let data = fetch_json(ctx, &url, &cache_file, CACHE_DURATION, BACKOFF_FILE).ok()?;
+    parse_nowcast(&data)
+}
+
+fn parse_nowcast(data: &Value) -> Option<Nowcast> {
+    if data["properties"]["meta"]["radar_coverage"].as_str() != Some("ok") {
+        return None;
+    }
+
+    let rates = data["properties"]["timeseries"]
+        .as_array()?
+        .iter()
+        .filter_map(|entry| {
+            let time = entry["time"].as_str()?.parse::<DateTime<Utc>>().ok()?;
+            let rate = entry["data"]["instant"]["details"]["precipitation_rate"].as_f64()?;
+            Some((time, rate))
+        })
+        .collect::<Vec<_>>();
+
+    (!rates.is_empty()).then_some(Nowcast { rates })
+}
+
+impl Nowcast {
+    /// Rates from the interval covering `now` onwards.
+    pub fn upcoming(&self, now: DateTime<Utc>) -> &[(DateTime<Utc>, f64)] {
+        let current = self.rates.iter().rposition(|(time, _)| *time <= now).unwrap_or(0);
+        &self.rates[current..]
+    }
+
+    /// When precipitation starts or stops within the nowcast period, if it does.
+    pub fn precipitation_change(&self, now: DateTime<Utc>) -> Option<PrecipitationChange> {
+        let upcoming = self.upcoming(now);
+        let (_, current_rate) = upcoming.first()?;
+        let raining = *current_rate >= RAIN_THRESHOLD;
+
+        let (time, _) = upcoming
+            .iter()
+            .skip(1)
+            .find(|(_, rate)| (*rate >= RAIN_THRESHOLD) != raining)?;
+        let minutes = (*time - now).num_minutes().max(0);
+
+        Some(if raining {
+            PrecipitationChange::StopsIn(minutes)
+        } else {
+            PrecipitationChange::StartsIn(minutes)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Cache;
+    use crate::clock::FixedClock;
+    use crate::http::mock::FixtureTransport;
+
+    const NOWCAST_FIXTURE: &str = include_str!("../tests/fixtures/met_nowcast_complete.json");
+    const NOWCAST_URL: &str = "https://api.met.no/weatherapi/nowcast/2.0/complete";
+    const OSLO: Coordinates = Coordinates { lat: 59.9139, lon: 10.7522, altitude: None };
+
+    fn nowcast() -> Nowcast {
+        parse_nowcast(&serde_json::from_str(NOWCAST_FIXTURE).unwrap()).unwrap()
+    }
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn predicts_when_rain_starts() {
+        assert_eq!(
+            nowcast().precipitation_change(at("2024-06-01T12:22:00Z")),
+            Some(PrecipitationChange::StartsIn(13))
+        );
+    }
+
+    #[test]
+    fn predicts_when_rain_stops() {
+        assert_eq!(
+            nowcast().precipitation_change(at("2024-06-01T12:50:00Z")),
+            Some(PrecipitationChange::StopsIn(40))
+        );
+    }
+
+    #[test]
+    fn reports_no_change_when_weather_stays_the_same() {
+        assert_eq!(nowcast().precipitation_change(at("2024-06-01T14:20:00Z")), None);
+    }
+
+    #[test]
+    fn backs_off_when_throttled() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new(at("2024-06-01T12:20:00Z"));
+        let throttled = FixtureTransport::default().route_with_headers(NOWCAST_URL, 429, &[("retry-after", "120")], "");
+        let ctx = Context { clock: &clock, http: &throttled, cache: &cache };
+
+        assert!(fetch_nowcast(&ctx, &OSLO).is_none());
+        assert!(fetch_nowcast(&ctx, &OSLO).is_none());
+        assert_eq!(throttled.requests.borrow().len(), 1);
+
+        clock.advance(chrono::Duration::minutes(3));
+        let http = FixtureTransport::default().route(NOWCAST_URL, 200, NOWCAST_FIXTURE);
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+        assert!(fetch_nowcast(&ctx, &OSLO).is_some());
+    }
+
+    #[test]
+    fn ignores_nowcast_without_radar_coverage() {
+        let mut data: Value = serde_json::from_str(NOWCAST_FIXTURE).unwrap();
+        data["properties"]["meta"]["radar_coverage"] = "temporarily unavailable".into();
+        assert!(parse_nowcast(&data).is_none());
+    }
+}
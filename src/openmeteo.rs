@@ -0,0 +1,125 @@
+// Weather forecast from Open-Meteo, which blends national weather models for every location
+// https://open-meteo.com/en/docs
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::forecast::{add_six_hour_totals, ForecastEntry, Period, WeatherProvider};
+use crate::format::{with_variant, SymbolVariant};
+use crate::location::Coordinates;
+
+/// Open-Meteo's hourly forecast for the next week
+pub struct OpenMeteo;
+
+impl WeatherProvider for OpenMeteo {
+    fn name(&self) -> &'static str {
+        "openmeteo"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Open-Meteo"
+    }
+
+    fn url(&self, coords: &Coordinates, _now: DateTime<Utc>) -> String {
+        let elevation = coords.altitude.map(|altitude| format!("&elevation={}", altitude)).unwrap_or_default();
+        format!(
+            "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}{}&hourly=temperature_2m,relative_humidity_2m,wind_speed_10m,wind_gusts_10m,precipitation,precipitation_probability,weather_code,uv_index_clear_sky,is_day&wind_speed_unit=ms&timeformat=unixtime&forecast_days=7",
+            coords.lat, coords.lon, elevation
+        )
+    }
+
+    fn parse(&self, data: &Value) -> Result<Vec<ForecastEntry>> {
+        let hourly = &data["hourly"];
+        let times = hourly["time"]
+            .as_array()
+            .ok_or_else(|| Error::Parse("missing hourly.time".to_string()))?;
+        let value = |key: &str, i: usize| hourly[key][i].as_f64();
+
+        let mut timeseries: Vec<ForecastEntry> = times
+            .iter()
+            .enumerate()
+            .filter_map(|(i, time)| {
+                let variant = match hourly["is_day"][i].as_u64() {
+                    Some(0) => SymbolVariant::Night,
+                    _ => SymbolVariant::Day,
+                };
+                Some(ForecastEntry {
+                    time: DateTime::from_timestamp(time.as_i64()?, 0)?,
+                    temperature: value("temperature_2m", i),
+                    wind_speed: value("wind_speed_10m", i),
+                    wind_gust: value("wind_gusts_10m", i),
+                    humidity: value("relative_humidity_2m", i),
+                    uv_index: value("uv_index_clear_sky", i),
+                    temperature_range: None,
+                    next_hour: Some(Period {
+                        symbol_code: hourly["weather_code"][i]
+                            .as_u64()
+                            .and_then(symbol_code)
+                            .map(|base_symbol| with_variant(base_symbol, variant)),
+                        // Precipitation is summed over the hour before each time
+                        precipitation: value("precipitation", i + 1),
+                        precipitation_range: None,
+                        probability: value("precipitation_probability", i + 1),
+                    }),
+                    next_6_hours: None,
+                })
+            })
+            .collect();
+        add_six_hour_totals(&mut timeseries);
+        Ok(timeseries)
+    }
+}
+
+/// MET base symbol code for a WMO weather interpretation code
+fn symbol_code(weather_code: u64) -> Option<&'static str> {
+    Some(match weather_code {
+        0 => "clearsky",
+        1 => "fair",
+        2 => "partlycloudy",
+        3 => "cloudy",
+        45 | 48 => "fog",
+        51 | 53 | 61 => "lightrain",
+        55 | 63 => "rain",
+        65 => "heavyrain",
+        56 | 66 => "lightsleet",
+        57 | 67 => "sleet",
+        71 | 77 => "lightsnow",
+        73 => "snow",
+        75 => "heavysnow",
+        80 => "lightrainshowers",
+        81 => "rainshowers",
+        82 => "heavyrainshowers",
+        85 => "lightsnowshowers",
+        86 => "heavysnowshowers",
+        95 => "rainandthunder",
+        96 | 99 => "heavyrainandthunder",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPEN_METEO_FIXTURE: &str = include_str!("../tests/fixtures/openmeteo_forecast.json");
+
+    #[test]
+    fn normalizes_hourly_forecast() {
+        let data = serde_json::from_str(OPEN_METEO_FIXTURE).unwrap();
+        let timeseries = OpenMeteo.parse(&data).unwrap();
+        assert_eq!(timeseries.len(), 12);
+
+        let first = &timeseries[0];
+        assert_eq!(first.time, "2024-06-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert_eq!(first.temperature, Some(17.4));
+        assert_eq!(first.uv_index, Some(6.9));
+        assert_eq!(first.wind_gust, Some(7.3));
+        assert_eq!(first.next_hour.as_ref().unwrap().symbol_code.as_deref(), Some("fair_day"));
+
+        let rainy = &timeseries[9];
+        assert_eq!(rainy.next_hour, Some(Period { symbol_code: Some("lightrain".to_string()), precipitation: Some(0.8), precipitation_range: None, probability: Some(65.0) }));
+        let six_hours = timeseries[5].next_6_hours.as_ref().and_then(|period| period.precipitation).unwrap();
+        assert!((six_hours - 1.6).abs() < 1e-9);
+        assert!(timeseries[6].next_6_hours.is_none());
+    }
+}
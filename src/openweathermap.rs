@@ -0,0 +1,137 @@
+// 5 day / 3 hour forecast from OpenWeatherMap, which needs a free API key
+// https://openweathermap.org/forecast5
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::forecast::{ForecastEntry, Period, WeatherProvider};
+use crate::format::{with_variant, SymbolVariant};
+use crate::location::Coordinates;
+
+/// OpenWeatherMap's forecast in 3-hour steps
+pub struct OpenWeatherMap {
+    api_key: String,
+}
+
+impl OpenWeatherMap {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key }
+    }
+}
+
+impl WeatherProvider for OpenWeatherMap {
+    fn name(&self) -> &'static str {
+        "openweathermap"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "OpenWeatherMap"
+    }
+
+    fn url(&self, coords: &Coordinates, _now: DateTime<Utc>) -> String {
+        format!(
+            "https://api.openweathermap.org/data/2.5/forecast?lat={}&lon={}&units=metric&appid={}",
+            coords.lat, coords.lon, self.api_key
+        )
+    }
+
+    /// The forecast has no hourly steps, so the next hour gets a third of the precipitation
+    /// in the 3-hour step and the next 6 hours that of two steps.
+    fn parse(&self, data: &Value) -> Result<Vec<ForecastEntry>> {
+        let list = data["list"]
+            .as_array()
+            .ok_or_else(|| Error::Parse("missing list".to_string()))?;
+        let precipitation = |step: &Value| {
+            step["rain"]["3h"].as_f64().unwrap_or(0.0) + step["snow"]["3h"].as_f64().unwrap_or(0.0)
+        };
+
+        Ok(list
+            .iter()
+            .enumerate()
+            .filter_map(|(i, step)| {
+                let weather = &step["weather"][0];
+                let variant = match weather["icon"].as_str() {
+                    Some(icon) if icon.ends_with('n') => SymbolVariant::Night,
+                    _ => SymbolVariant::Day,
+                };
+                Some(ForecastEntry {
+                    time: DateTime::from_timestamp(step["dt"].as_i64()?, 0)?,
+                    temperature: step["main"]["temp"].as_f64(),
+                    wind_speed: step["wind"]["speed"].as_f64(),
+                    wind_gust: step["wind"]["gust"].as_f64(),
+                    humidity: step["main"]["humidity"].as_f64(),
+                    uv_index: None,
+                    temperature_range: None,
+                    next_hour: Some(Period {
+                        symbol_code: weather["id"]
+                            .as_u64()
+                            .and_then(symbol_code)
+                            .map(|base_symbol| with_variant(base_symbol, variant)),
+                        precipitation: Some(precipitation(step) / 3.0),
+                        precipitation_range: None,
+                        // `pop` is the chance of precipitation in the 3-hour step, from 0 to 1
+                        probability: step["pop"].as_f64().map(|pop| pop * 100.0),
+                    }),
+                    next_6_hours: list.get(i + 1).map(|next| Period {
+                        precipitation: Some(precipitation(step) + precipitation(next)),
+                        ..Default::default()
+                    }),
+                })
+            })
+            .collect())
+    }
+}
+
+/// MET base symbol code for an OpenWeatherMap condition ID
+/// https://openweathermap.org/weather-conditions
+fn symbol_code(condition: u64) -> Option<&'static str> {
+    Some(match condition {
+        200 | 230..=232 => "lightrainandthunder",
+        201 | 210..=221 => "rainandthunder",
+        202 => "heavyrainandthunder",
+        300..=321 | 500 => "lightrain",
+        501 => "rain",
+        502..=504 => "heavyrain",
+        511 | 611..=616 => "sleet",
+        520 => "lightrainshowers",
+        521 => "rainshowers",
+        522 | 531 => "heavyrainshowers",
+        600 => "lightsnow",
+        601 => "snow",
+        602 => "heavysnow",
+        620 => "lightsnowshowers",
+        621 => "snowshowers",
+        622 => "heavysnowshowers",
+        701..=781 => "fog",
+        800 => "clearsky",
+        801 => "fair",
+        802 => "partlycloudy",
+        803 | 804 => "cloudy",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPEN_WEATHER_MAP_FIXTURE: &str = include_str!("../tests/fixtures/openweathermap_forecast.json");
+
+    #[test]
+    fn normalizes_three_hour_forecast() {
+        let data = serde_json::from_str(OPEN_WEATHER_MAP_FIXTURE).unwrap();
+        let timeseries = OpenWeatherMap::new("key".to_string()).parse(&data).unwrap();
+        assert_eq!(timeseries.len(), 6);
+
+        let first = &timeseries[0];
+        assert_eq!(first.time, "2024-06-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert_eq!(first.temperature, Some(19.2));
+        assert_eq!(first.next_hour.as_ref().unwrap().symbol_code.as_deref(), Some("clearsky_day"));
+
+        let rainy = &timeseries[3];
+        assert_eq!(rainy.next_hour, Some(Period { symbol_code: Some("rain".to_string()), precipitation: Some(0.5), precipitation_range: None, probability: Some(70.0) }));
+        assert_eq!(timeseries[2].next_6_hours.as_ref().unwrap().precipitation, Some(2.25));
+        assert_eq!(timeseries[4].next_hour.as_ref().unwrap().symbol_code.as_deref(), Some("cloudy"));
+        assert!(timeseries[5].next_6_hours.is_none());
+    }
+}
@@ -0,0 +1,289 @@
+// Pollen forecast for birch, grass and mugwort from a pluggable provider
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::forecast::{fetch_json, read_stale_json, Context};
+use crate::location::Coordinates;
+
+/// Pollen types in the forecast
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollenType {
+    Birch,
+    Grass,
+    Mugwort,
+}
+
+impl PollenType {
+    pub const ALL: [PollenType; 3] = [Self::Birch, Self::Grass, Self::Mugwort];
+
+    /// Lowest daily peak in grains/m³ for the low, moderate, high and very high levels,
+    /// after the Norwegian Asthma and Allergy Association's pollen forecast
+    fn thresholds(&self) -> [f64; 4] {
+        match self {
+            Self::Birch => [1.0, 10.0, 100.0, 1000.0],
+            Self::Grass => [1.0, 10.0, 30.0, 150.0],
+            Self::Mugwort => [1.0, 5.0, 15.0, 50.0],
+        }
+    }
+}
+
+/// Pollen level for a day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PollenLevel {
+    None,
+    Low,
+    Moderate,
+    High,
+    VeryHigh,
+}
+
+impl PollenLevel {
+    /// Level of a daily peak concentration in grains/m³
+    pub fn from_concentration(pollen_type: PollenType, grains: f64) -> Self {
+        let [low, moderate, high, very_high] = pollen_type.thresholds();
+        if grains >= very_high {
+            Self::VeryHigh
+        } else if grains >= high {
+            Self::High
+        } else if grains >= moderate {
+            Self::Moderate
+        } else if grains >= low {
+            Self::Low
+        } else {
+            Self::None
+        }
+    }
+
+    /// CSS class for the indicator
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::None => "pollen-none",
+            Self::Low => "pollen-low",
+            Self::Moderate => "pollen-moderate",
+            Self::High => "pollen-high",
+            Self::VeryHigh => "pollen-very-high",
+        }
+    }
+}
+
+/// Pollen levels for one day. Types without a forecast are `None`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PollenDay {
+    pub date: NaiveDate,
+    pub birch: Option<PollenLevel>,
+    pub grass: Option<PollenLevel>,
+    pub mugwort: Option<PollenLevel>,
+}
+
+impl PollenDay {
+    pub fn level(&self, pollen_type: PollenType) -> Option<PollenLevel> {
+        match pollen_type {
+            PollenType::Birch => self.birch,
+            PollenType::Grass => self.grass,
+            PollenType::Mugwort => self.mugwort,
+        }
+    }
+
+    /// The pollen type with the highest level, if any has a forecast
+    pub fn worst(&self) -> Option<(PollenType, PollenLevel)> {
+        PollenType::ALL
+            .into_iter()
+            .filter_map(|pollen_type| Some((pollen_type, self.level(pollen_type)?)))
+            .max_by_key(|(_, level)| *level)
+    }
+}
+
+/// Source of pollen forecasts, so local files and web services can be used alike
+pub trait PollenProvider {
+    /// Pollen levels per day, starting today in the location's time zone.
+    fn fetch(&self, ctx: &Context, coords: &Coordinates) -> Result<Vec<PollenDay>>;
+}
+
+/// Reads the forecast from a JSON file, e.g. for testing or for data from another source:
+/// `{"days": [{"date": "2024-06-01", "birch": "low", "grass": "high", "mugwort": "none"}]}`
+pub struct FileProvider {
+    path: PathBuf,
+}
+
+impl FileProvider {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl PollenProvider for FileProvider {
+    fn fetch(&self, _ctx: &Context, _coords: &Coordinates) -> Result<Vec<PollenDay>> {
+        #[derive(Deserialize)]
+        struct PollenFile {
+            days: Vec<PollenDay>,
+        }
+
+        let contents = fs::read_to_string(&self.path).map_err(|e| Error::Config(format!("{}: {}", self.path.display(), e)))?;
+        let file: PollenFile =
+            serde_json::from_str(&contents).map_err(|e| Error::Parse(format!("{}: {}", self.path.display(), e)))?;
+        Ok(file.days)
+    }
+}
+
+/// Open-Meteo's air quality API, based on the CAMS European pollen forecast
+/// https://open-meteo.com/en/docs/air-quality-api
+pub struct OpenMeteoProvider;
+
+const BACKOFF_FILE: &str = "backoff-pollen.json";
+
+impl PollenProvider for OpenMeteoProvider {
+    /// Cached like the weather forecast: fresh for 15 minutes, and used however old if fetching fails.
+    fn fetch(&self, ctx: &Context, coords: &Coordinates) -> Result<Vec<PollenDay>> {
+        const CACHE_DURATION: Duration = Duration::from_secs(900); // 15 minutes
+        let cache_file = format!("pollen-{}-{}.json", coords.lat, coords.lon);
+
+        let url = format!(
+            "https://air-quality-api.open-meteo.com/v1/air-quality?latitude={}&longitude={}&hourly=birch_pollen,grass_pollen,mugwort_pollen&timezone=auto&forecast_days=2",
+            coords.lat, coords.lon
+        );
+        match fetch_json(ctx, &url, &cache_file, CACHE_DURATION, BACKOFF_FILE) {
+            Ok(data) => Ok(parse_open_meteo(&data)),
+            // Fall back to the last good forecast, however old
+            Err(e) => match read_stale_json(ctx, &cache_file) {
+                Some((data, age)) => {
+                    eprintln!("Warning: {}. Using cached pollen forecast from {} min ago.", e, age.as_secs() / 60);
+                    Ok(parse_open_meteo(&data))
+                }
+                None => Err(e),
+            },
+        }
+    }
+}
+
+/// Daily peaks of the hourly concentrations. Times are local to the location.
+fn parse_open_meteo(data: &Value) -> Vec<PollenDay> {
+    let hourly = &data["hourly"];
+    let times = hourly["time"].as_array().map(Vec::as_slice).unwrap_or_default();
+
+    let mut days: Vec<PollenDay> = Vec::new();
+    for (i, time) in times.iter().enumerate() {
+        let Some(date) = time.as_str().and_then(|time| time.get(..10)).and_then(|date| date.parse().ok()) else {
+            continue;
+        };
+        if days.last().map(|day| day.date) != Some(date) {
+            days.push(PollenDay { date, birch: None, grass: None, mugwort: None });
+        }
+        let day = days.last_mut().unwrap();
+
+        for (pollen_type, key, level) in [
+            (PollenType::Birch, "birch_pollen", &mut day.birch),
+            (PollenType::Grass, "grass_pollen", &mut day.grass),
+            (PollenType::Mugwort, "mugwort_pollen", &mut day.mugwort),
+        ] {
+            if let Some(grains) = hourly[key][i].as_f64() {
+                let hour_level = PollenLevel::from_concentration(pollen_type, grains);
+                *level = (*level).max(Some(hour_level));
+            }
+        }
+    }
+    days
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Cache;
+    use crate::clock::FixedClock;
+    use crate::http::mock::FixtureTransport;
+
+    const OPEN_METEO_FIXTURE: &str = include_str!("../tests/fixtures/openmeteo_pollen.json");
+    const OPEN_METEO_URL: &str = "https://air-quality-api.open-meteo.com/v1/air-quality";
+    const OSLO: Coordinates = Coordinates { lat: 59.9139, lon: 10.7522, altitude: None };
+
+    fn date(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    fn expected_days() -> Vec<PollenDay> {
+        vec![
+            PollenDay {
+                date: date("2024-06-01"),
+                birch: Some(PollenLevel::Low),
+                grass: Some(PollenLevel::High),
+                mugwort: Some(PollenLevel::None),
+            },
+            PollenDay {
+                date: date("2024-06-02"),
+                birch: Some(PollenLevel::None),
+                grass: Some(PollenLevel::Moderate),
+                mugwort: Some(PollenLevel::None),
+            },
+        ]
+    }
+
+    #[test]
+    fn converts_concentrations_to_levels() {
+        assert_eq!(PollenLevel::from_concentration(PollenType::Birch, 0.5), PollenLevel::None);
+        assert_eq!(PollenLevel::from_concentration(PollenType::Birch, 80.0), PollenLevel::Moderate);
+        assert_eq!(PollenLevel::from_concentration(PollenType::Grass, 80.0), PollenLevel::High);
+        assert_eq!(PollenLevel::from_concentration(PollenType::Mugwort, 80.0), PollenLevel::VeryHigh);
+    }
+
+    #[test]
+    fn reads_forecast_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new("2024-06-01T12:00:00Z".parse().unwrap());
+        let http = FixtureTransport::default();
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        let provider = FileProvider::new(PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/pollen.json")));
+        let days = provider.fetch(&ctx, &OSLO).unwrap();
+        let mut expected = expected_days();
+        expected[1].birch = None;
+        expected[1].mugwort = None;
+        assert_eq!(days, expected);
+        assert_eq!(days[0].worst(), Some((PollenType::Grass, PollenLevel::High)));
+
+        let missing = FileProvider::new(dir.path().join("missing.json"));
+        assert!(matches!(missing.fetch(&ctx, &OSLO), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn fetches_daily_peaks_from_open_meteo_and_falls_back_to_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new("2024-06-01T12:00:00Z".parse().unwrap());
+        let http = FixtureTransport::default().route(OPEN_METEO_URL, 200, OPEN_METEO_FIXTURE);
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+
+        assert_eq!(OpenMeteoProvider.fetch(&ctx, &OSLO).unwrap(), expected_days());
+        assert_eq!(OpenMeteoProvider.fetch(&ctx, &OSLO).unwrap(), expected_days());
+        assert_eq!(http.requests.borrow().len(), 1);
+
+        clock.advance(chrono::Duration::hours(2));
+        let failing = FixtureTransport::default().route(OPEN_METEO_URL, 503, "");
+        let ctx = Context { clock: &clock, http: &failing, cache: &cache };
+        assert_eq!(OpenMeteoProvider.fetch(&ctx, &OSLO).unwrap(), expected_days());
+    }
+
+    #[test]
+    fn backs_off_when_throttled() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let clock = FixedClock::new("2024-06-01T12:00:00Z".parse().unwrap());
+        let throttled = FixtureTransport::default().route_with_headers(OPEN_METEO_URL, 429, &[("retry-after", "120")], "");
+        let ctx = Context { clock: &clock, http: &throttled, cache: &cache };
+
+        assert!(matches!(OpenMeteoProvider.fetch(&ctx, &OSLO), Err(Error::Throttled(_))));
+        assert!(matches!(OpenMeteoProvider.fetch(&ctx, &OSLO), Err(Error::Throttled(_))));
+        assert_eq!(throttled.requests.borrow().len(), 1);
+
+        clock.advance(chrono::Duration::minutes(3));
+        let http = FixtureTransport::default().route(OPEN_METEO_URL, 200, OPEN_METEO_FIXTURE);
+        let ctx = Context { clock: &clock, http: &http, cache: &cache };
+        assert_eq!(OpenMeteoProvider.fetch(&ctx, &OSLO).unwrap(), expected_days());
+    }
+}
@@ -0,0 +1,122 @@
+// Sunrise, sunset and solar noon from NOAA's general solar position equations
+// https://gml.noaa.gov/grad/solcalc/solareqns.PDF
+use std::f64::consts::PI;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+
+/// Zenith angle of the sun's centre at sunrise and sunset, allowing for refraction and the solar disc
+const SUNRISE_ZENITH_DEGREES: f64 = 90.833;
+
+/// Daylight at a location on a given date
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Daylight {
+    Normal {
+        sunrise: DateTime<Utc>,
+        sunset: DateTime<Utc>,
+        solar_noon: DateTime<Utc>,
+    },
+    /// The sun stays above the horizon all day (midnight sun)
+    PolarDay { solar_noon: DateTime<Utc> },
+    /// The sun stays below the horizon all day
+    PolarNight { solar_noon: DateTime<Utc> },
+}
+
+impl Daylight {
+    pub fn solar_noon(&self) -> DateTime<Utc> {
+        match self {
+            Self::Normal { solar_noon, .. } | Self::PolarDay { solar_noon } | Self::PolarNight { solar_noon } => *solar_noon,
+        }
+    }
+
+    /// Time between sunrise and sunset
+    pub fn day_length(&self) -> Duration {
+        match self {
+            Self::Normal { sunrise, sunset, .. } => *sunset - *sunrise,
+            Self::PolarDay { .. } => Duration::hours(24),
+            Self::PolarNight { .. } => Duration::zero(),
+        }
+    }
+}
+
+/// Sunrise, sunset and solar noon on `date` at the given location, accurate to about a minute.
+pub fn daylight(date: NaiveDate, lat: f64, lon: f64) -> Daylight {
+    // Fractional year in radians, at noon
+    let gamma = 2.0 * PI / 365.0 * (date.ordinal0() as f64);
+    let eqtime = 229.18
+        * (0.000075 + 0.001868 * gamma.cos() - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+    let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let at_minutes = |minutes: f64| midnight + Duration::seconds((minutes * 60.0).round() as i64);
+    let solar_noon = at_minutes(720.0 - 4.0 * lon - eqtime);
+
+    let lat_rad = lat.to_radians();
+    let cos_hour_angle = SUNRISE_ZENITH_DEGREES.to_radians().cos() / (lat_rad.cos() * declination.cos())
+        - lat_rad.tan() * declination.tan();
+    if cos_hour_angle > 1.0 {
+        return Daylight::PolarNight { solar_noon };
+    }
+    if cos_hour_angle < -1.0 {
+        return Daylight::PolarDay { solar_noon };
+    }
+
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+    Daylight::Normal {
+        sunrise: at_minutes(720.0 - 4.0 * (lon + hour_angle) - eqtime),
+        sunset: at_minutes(720.0 - 4.0 * (lon - hour_angle) - eqtime),
+        solar_noon,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    fn assert_close(actual: DateTime<Utc>, expected: &str) {
+        let expected: DateTime<Utc> = expected.parse().unwrap();
+        assert!(
+            (actual - expected).num_minutes().abs() <= 2,
+            "expected {} but got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn computes_sunrise_and_sunset_in_oslo() {
+        let Daylight::Normal { sunrise, sunset, solar_noon } = daylight(date("2024-06-21"), 59.9139, 10.7522) else {
+            panic!("expected sunrise and sunset in Oslo");
+        };
+        assert_close(sunrise, "2024-06-21T01:53:00Z");
+        assert_close(sunset, "2024-06-21T20:44:00Z");
+        assert_close(solar_noon, "2024-06-21T11:19:00Z");
+
+        let Daylight::Normal { sunrise, sunset, .. } = daylight(date("2024-12-21"), 59.9139, 10.7522) else {
+            panic!("expected sunrise and sunset in Oslo");
+        };
+        assert_close(sunrise, "2024-12-21T08:18:00Z");
+        assert_close(sunset, "2024-12-21T14:12:00Z");
+    }
+
+    #[test]
+    fn handles_polar_day_and_night_in_tromso() {
+        let summer = daylight(date("2024-06-21"), 69.6496, 18.9560);
+        assert!(matches!(summer, Daylight::PolarDay { .. }));
+        assert_eq!(summer.day_length(), Duration::hours(24));
+
+        let winter = daylight(date("2024-12-21"), 69.6496, 18.9560);
+        assert!(matches!(winter, Daylight::PolarNight { .. }));
+        assert_eq!(winter.day_length(), Duration::zero());
+        assert_close(winter.solar_noon(), "2024-12-21T10:42:00Z");
+    }
+}
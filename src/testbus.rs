@@ -0,0 +1,43 @@
+// Private D-Bus daemon for tests, so they do not depend on a session bus being around
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+
+use zbus::blocking::connection::Builder;
+use zbus::blocking::Connection;
+
+/// A `dbus-daemon` of its own, stopped when dropped.
+pub struct PrivateBus {
+    daemon: Child,
+    address: String,
+}
+
+impl PrivateBus {
+    /// Start a daemon with the session bus configuration. Panics if `dbus-daemon` is not installed.
+    pub fn start() -> Self {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("dbus-daemon is needed for D-Bus tests");
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap()).read_line(&mut address).unwrap();
+        Self { daemon, address: address.trim().to_string() }
+    }
+
+    /// Builder for a connection, e.g. to serve a mock service.
+    pub fn builder(&self) -> Builder<'_> {
+        Builder::address(self.address.as_str()).unwrap()
+    }
+
+    pub fn connect(&self) -> Connection {
+        self.builder().build().unwrap()
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
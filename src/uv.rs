@@ -0,0 +1,90 @@
+// UV index categories from the WHO's Global Solar UV Index guide
+// https://www.who.int/publications/i/item/9241590076
+use chrono::{NaiveDate, TimeZone};
+
+use crate::forecast::ForecastEntry;
+
+/// WHO exposure category of a UV index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UvCategory {
+    Low,
+    Moderate,
+    High,
+    VeryHigh,
+    Extreme,
+}
+
+impl UvCategory {
+    pub fn from_index(uv_index: f64) -> Self {
+        match uv_index.round() as i64 {
+            ..=2 => Self::Low,
+            3..=5 => Self::Moderate,
+            6..=7 => Self::High,
+            8..=10 => Self::VeryHigh,
+            _ => Self::Extreme,
+        }
+    }
+
+    /// CSS class for the indicator
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::Low => "uv-low",
+            Self::Moderate => "uv-moderate",
+            Self::High => "uv-high",
+            Self::VeryHigh => "uv-very-high",
+            Self::Extreme => "uv-extreme",
+        }
+    }
+}
+
+/// Highest clear sky UV index forecast for `date` in the time zone `tz`.
+pub fn daily_max_uv_index<Tz: TimeZone>(timeseries: &[ForecastEntry], date: NaiveDate, tz: &Tz) -> Option<f64> {
+    timeseries
+        .iter()
+        .filter(|entry| entry.time.with_timezone(tz).date_naive() == date)
+        .filter_map(|entry| entry.uv_index)
+        .reduce(f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, FixedClock};
+    use crate::forecast::{skip_past_entries, WeatherProvider};
+    use crate::met::Met;
+    use chrono::Utc;
+
+    const MET_FIXTURE: &str = include_str!("../tests/fixtures/met_locationforecast_complete.json");
+
+    #[test]
+    fn categorises_uv_index() {
+        assert_eq!(UvCategory::from_index(0.0), UvCategory::Low);
+        assert_eq!(UvCategory::from_index(2.4), UvCategory::Low);
+        assert_eq!(UvCategory::from_index(2.5), UvCategory::Moderate);
+        assert_eq!(UvCategory::from_index(7.2), UvCategory::High);
+        assert_eq!(UvCategory::from_index(10.0), UvCategory::VeryHigh);
+        assert_eq!(UvCategory::from_index(11.3), UvCategory::Extreme);
+    }
+
+    #[test]
+    fn finds_daily_maximum() {
+        let timeseries = Met.parse(&serde_json::from_str(MET_FIXTURE).unwrap()).unwrap();
+        let date = |date: &str| date.parse::<NaiveDate>().unwrap();
+        assert_eq!(daily_max_uv_index(&timeseries, date("2024-06-01"), &Utc), Some(7.1));
+        assert_eq!(daily_max_uv_index(&timeseries, date("2024-06-02"), &Utc), Some(7.2));
+        assert_eq!(daily_max_uv_index(&timeseries, date("2024-07-01"), &Utc), None);
+    }
+
+    #[test]
+    fn keeps_peak_that_has_passed() {
+        let clock = FixedClock::new("2024-06-01T16:20:00Z".parse().unwrap());
+        let mut timeseries = Met.parse(&serde_json::from_str(MET_FIXTURE).unwrap()).unwrap();
+        let today = clock.now().date_naive();
+
+        let max_today = daily_max_uv_index(&timeseries, today, &Utc);
+        skip_past_entries(&mut timeseries, clock.now());
+        assert_eq!(max_today, Some(7.1));
+        // The remaining hours alone would understate the peak
+        assert!(daily_max_uv_index(&timeseries, today, &Utc).unwrap() < 7.1);
+    }
+}
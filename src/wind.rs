@@ -0,0 +1,81 @@
+// Beaufort wind force scale, from the WMO's Manual on Codes
+// https://library.wmo.int/idurl/4/35713
+use crate::config::Config;
+
+/// Beaufort force of a mean wind speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Beaufort {
+    Calm,
+    LightAir,
+    LightBreeze,
+    GentleBreeze,
+    ModerateBreeze,
+    FreshBreeze,
+    StrongBreeze,
+    NearGale,
+    Gale,
+    StrongGale,
+    Storm,
+    ViolentStorm,
+    Hurricane,
+}
+
+impl Beaufort {
+    /// Force for a wind speed in m/s, rounded to one decimal as the scale is given
+    pub fn from_speed(wind_speed: f64) -> Self {
+        match (wind_speed * 10.0).round() as i64 {
+            ..=2 => Self::Calm,
+            3..=15 => Self::LightAir,
+            16..=33 => Self::LightBreeze,
+            34..=54 => Self::GentleBreeze,
+            55..=79 => Self::ModerateBreeze,
+            80..=107 => Self::FreshBreeze,
+            108..=138 => Self::StrongBreeze,
+            139..=171 => Self::NearGale,
+            172..=207 => Self::Gale,
+            208..=244 => Self::StrongGale,
+            245..=284 => Self::Storm,
+            285..=326 => Self::ViolentStorm,
+            _ => Self::Hurricane,
+        }
+    }
+
+    /// Number on the scale, from 0 to 12
+    pub fn force(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// CSS class for gusts reaching the thresholds in the config, if they do
+pub fn gust_class(wind_gust: f64, config: &Config) -> Option<&'static str> {
+    if wind_gust >= config.storm_gust {
+        Some("storm")
+    } else if wind_gust >= config.windy_gust {
+        Some("windy")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn categorises_wind_speed() {
+        assert_eq!(Beaufort::from_speed(0.0), Beaufort::Calm);
+        assert_eq!(Beaufort::from_speed(3.3), Beaufort::LightBreeze);
+        assert_eq!(Beaufort::from_speed(3.36), Beaufort::GentleBreeze);
+        assert_eq!(Beaufort::from_speed(5.5), Beaufort::ModerateBreeze);
+        assert_eq!(Beaufort::from_speed(12.0).force(), 6);
+        assert_eq!(Beaufort::from_speed(40.0), Beaufort::Hurricane);
+    }
+
+    #[test]
+    fn classifies_gusts_by_config_thresholds() {
+        let config = Config::default();
+        assert_eq!(gust_class(13.8, &config), None);
+        assert_eq!(gust_class(13.9, &config), Some("windy"));
+        assert_eq!(gust_class(24.5, &config), Some("storm"));
+    }
+}